@@ -0,0 +1,130 @@
+/*!
+ * Description:
+ *
+ * Fits a board and the panel of text beside it into whatever size the terminal is.
+ *
+ * The board is drawn at the zoom the player picked if there's room, otherwise at the
+ * closest smaller zoom that fits. The panel goes to the right of the board when it's
+ * wide enough, underneath when it's tall enough, and is left out when neither is. When
+ * the board won't fit even at the smallest zoom the screen says so instead.
+ *
+ * Layouts are worked out from the frame's size on every draw, and ratatui resizes its
+ * buffers when the terminal does, so a resize shows up on the very next frame.
+ *
+ * Usage:
+ *
+ *   let Some(layout) = board_layout::split(frame.area(), &world, zoom, PANEL) else {
+ *       board_layout::too_small(frame, &world);
+ *       return;
+ *   };
+ */
+
+use crate::types::{World, Zoom};
+use ratatui::prelude::*;
+use ratatui::{
+    widgets::{Block, Clear, Paragraph, Wrap},
+    Frame,
+};
+
+/// The smallest a panel can be and still be worth showing, in columns and rows
+#[derive(Debug, Clone, Copy)]
+pub struct PanelSize {
+    pub width: u16,
+    pub height: u16,
+}
+
+/// Where a board screen draws everything
+#[derive(Debug, Clone, PartialEq)]
+pub struct BoardLayout {
+    /// The bordered box for the board
+    pub board: Rect,
+    /// The zoom that fits, which can be smaller than the one asked for
+    pub zoom: Zoom,
+    /// The bordered box for the panel, if there's room for it
+    pub panel: Option<Rect>,
+}
+
+/// The columns and rows a board takes up at `zoom`, not counting its border. Tiles are
+/// as many pixels across as the zoom's scale and two pixels fit in each row.
+pub fn board_size(world: &World, zoom: Zoom) -> (u16, u16) {
+    let (rows, cols) = world.board.dim();
+    let pixels = match zoom {
+        Zoom::Close => 4,
+        Zoom::Middle => 2,
+        Zoom::Far => 1,
+    };
+    ((cols * pixels) as u16, (rows * pixels).div_ceil(2) as u16)
+}
+
+/// The biggest zoom no closer than `zoom` that fits the board inside `area`
+pub fn fit_zoom(world: &World, zoom: Zoom, area: Rect) -> Option<Zoom> {
+    [Zoom::Close, Zoom::Middle, Zoom::Far]
+        .into_iter()
+        .skip_while(|&candidate| candidate != zoom)
+        .find(|&candidate| {
+            let (width, height) = board_size(world, candidate);
+            width + 2 <= area.width && height + 2 <= area.height
+        })
+}
+
+/// Lays out the board and the panel in `area`, or `None` if the board can't fit
+pub fn split(
+    area: Rect,
+    world: &World,
+    zoom: Zoom,
+    panel: PanelSize,
+) -> Option<BoardLayout> {
+    let zoom = fit_zoom(world, zoom, area)?;
+    let (width, height) = board_size(world, zoom);
+    let (board_width, board_height) = (width + 2, height + 2);
+
+    if area.width >= board_width + panel.width {
+        // Side by side, sharing the width evenly when the board doesn't need more
+        let board_width = (area.width / 2)
+            .max(board_width)
+            .min(area.width - panel.width);
+        let [board, panel] =
+            Layout::horizontal([Constraint::Length(board_width), Constraint::Min(0)])
+                .areas(area);
+        return Some(BoardLayout {
+            board,
+            zoom,
+            panel: Some(panel),
+        });
+    }
+    if area.height >= board_height + panel.height {
+        let [board, panel] =
+            Layout::vertical([Constraint::Length(board_height), Constraint::Min(0)])
+                .areas(area);
+        return Some(BoardLayout {
+            board,
+            zoom,
+            panel: Some(panel),
+        });
+    }
+    Some(BoardLayout {
+        board: area,
+        zoom,
+        panel: None,
+    })
+}
+
+/// Fills the screen with a note saying how big the terminal needs to be for `world`
+pub fn too_small(frame: &mut Frame, world: &World) {
+    let (width, height) = board_size(world, Zoom::Far);
+    let text = format!(
+        "Terminal too small\n\n{} needs at least {}×{}, make the window bigger",
+        world.name,
+        width + 2,
+        height + 2
+    );
+    let area = frame.area();
+    frame.render_widget(Clear, area);
+    frame.render_widget(
+        Paragraph::new(text)
+            .alignment(Alignment::Center)
+            .wrap(Wrap { trim: true })
+            .block(Block::bordered()),
+        area,
+    );
+}
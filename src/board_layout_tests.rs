@@ -0,0 +1,90 @@
+#[cfg(test)]
+mod tests {
+    use crate::board_layout::{board_size, fit_zoom, split, PanelSize};
+    use crate::test_util::load_world;
+    use crate::types::Zoom;
+    use ratatui::layout::Rect;
+
+    // 7 wide and 5 tall
+    const LEVEL: &str = "; Test
+#######
+#     #
+# $@  #
+#.    #
+#######
+";
+
+    const PANEL: PanelSize = PanelSize {
+        width: 30,
+        height: 6,
+    };
+
+    fn area(width: u16, height: u16) -> Rect {
+        Rect::new(0, 0, width, height)
+    }
+
+    #[test]
+    fn test_board_size_at_each_zoom() {
+        let world = load_world(LEVEL);
+        assert_eq!(board_size(&world, Zoom::Close), (28, 10));
+        assert_eq!(board_size(&world, Zoom::Middle), (14, 5));
+        // Odd rows round up to a whole row of cells
+        assert_eq!(board_size(&world, Zoom::Far), (7, 3));
+    }
+
+    #[test]
+    fn test_zooms_out_until_the_board_fits() {
+        let world = load_world(LEVEL);
+        assert_eq!(
+            fit_zoom(&world, Zoom::Close, area(30, 12)),
+            Some(Zoom::Close)
+        );
+        assert_eq!(
+            fit_zoom(&world, Zoom::Close, area(29, 12)),
+            Some(Zoom::Middle)
+        );
+        // Never closer than asked for
+        assert_eq!(
+            fit_zoom(&world, Zoom::Middle, area(80, 40)),
+            Some(Zoom::Middle)
+        );
+        assert_eq!(fit_zoom(&world, Zoom::Close, area(9, 5)), Some(Zoom::Far));
+        assert_eq!(fit_zoom(&world, Zoom::Close, area(8, 5)), None);
+    }
+
+    #[test]
+    fn test_wide_terminal_splits_evenly() {
+        let world = load_world(LEVEL);
+        let layout = split(area(100, 30), &world, Zoom::Middle, PANEL).unwrap();
+        assert_eq!(layout.board, Rect::new(0, 0, 50, 30));
+        assert_eq!(layout.panel, Some(Rect::new(50, 0, 50, 30)));
+    }
+
+    #[test]
+    fn test_panel_keeps_its_width_next_to_a_small_board() {
+        let world = load_world(LEVEL);
+        let layout = split(area(50, 30), &world, Zoom::Middle, PANEL).unwrap();
+        assert_eq!(layout.board.width, 20);
+        assert_eq!(layout.panel.map(|panel| panel.width), Some(30));
+    }
+
+    #[test]
+    fn test_narrow_terminal_puts_the_panel_underneath() {
+        let world = load_world(LEVEL);
+        let layout = split(area(30, 20), &world, Zoom::Close, PANEL).unwrap();
+        assert_eq!(layout.zoom, Zoom::Close);
+        assert_eq!(layout.board, Rect::new(0, 0, 30, 12));
+        assert_eq!(layout.panel, Some(Rect::new(0, 12, 30, 8)));
+    }
+
+    #[test]
+    fn test_small_terminal_leaves_the_panel_out() {
+        let world = load_world(LEVEL);
+        let layout = split(area(20, 10), &world, Zoom::Close, PANEL).unwrap();
+        assert_eq!(layout.zoom, Zoom::Middle);
+        assert_eq!(layout.board, area(20, 10));
+        assert_eq!(layout.panel, None);
+
+        assert_eq!(split(area(8, 4), &world, Zoom::Close, PANEL), None);
+    }
+}
@@ -0,0 +1,238 @@
+/*!
+ * Description:
+ *
+ * Command line parsing. With no arguments termban starts the game as usual, anything
+ * else picks one of the subcommands below.
+ *
+ * Usage:
+ *
+ *   termban                            Play
+ *   termban replay <level> <solution>  Watch a LURD solution for a level
+ *   termban render <file> [options]    Draw a level to a PNG, SVG or ANSI text file
+ *   termban record <file> [options]    Record a solution as an asciicast or a GIF
+ *   termban import <file> <solutions>  Store the solutions to a file's levels
+ *   termban dedup <files...>           List the levels that appear more than once
+ *   termban generate [options]         Make up new levels, then play them
+ *
+ * `--develop` can go anywhere on the line to open every level.
+ */
+
+use std::path::PathBuf;
+
+use crate::copy_text;
+use crate::generator::{self, Settings};
+use crate::types::Zoom;
+
+#[derive(Debug, PartialEq)]
+pub enum Command {
+    Play,
+    /// Open the replay viewer on a (1 based) level number with a LURD solution
+    Replay {
+        level: usize,
+        solution: String,
+    },
+    /// Draw a (1 based) level of a level file, after playing `moves`, to `output` or
+    /// stdout
+    Render {
+        file: PathBuf,
+        level: usize,
+        moves: String,
+        zoom: Zoom,
+        output: Option<PathBuf>,
+    },
+    /// Record `moves` being played on a (1 based) level of a level file, at `speed`
+    /// moves a second, to `output` or stdout
+    Record {
+        file: PathBuf,
+        level: usize,
+        moves: String,
+        zoom: Zoom,
+        speed: u64,
+        output: Option<PathBuf>,
+    },
+    /// Verify and store the solutions in `solutions` for the levels in `levels`
+    Import {
+        levels: PathBuf,
+        solutions: PathBuf,
+    },
+    /// List the levels that are the same as another among `files`
+    Dedup {
+        files: Vec<PathBuf>,
+    },
+    /// Make up levels and write them to `output`, or the player's levels directory
+    Generate {
+        settings: Settings,
+        output: Option<PathBuf>,
+    },
+}
+
+#[derive(Debug, PartialEq)]
+pub struct Args {
+    pub command: Command,
+    /// Open every level, whatever the unlock rules say
+    pub develop: bool,
+}
+
+pub fn parse(args: &[String]) -> Result<Args, String> {
+    let develop = args.iter().any(|arg| arg == "--develop");
+    let args: Vec<String> = args
+        .iter()
+        .filter(|arg| *arg != "--develop")
+        .cloned()
+        .collect();
+    Ok(Args {
+        command: parse_command(&args)?,
+        develop,
+    })
+}
+
+fn parse_command(args: &[String]) -> Result<Command, String> {
+    match args {
+        [] => Ok(Command::Play),
+        [command, rest @ ..] if command == "replay" => match rest {
+            [level, solution @ ..] if !solution.is_empty() => Ok(Command::Replay {
+                level: parse_level(level)?,
+                solution: solution.concat(),
+            }),
+            _ => Err(copy_text::USAGE.to_string()),
+        },
+        [command, file, options @ ..] if command == "render" || command == "record" => {
+            parse_drawing(command, file, options)
+        }
+        [command, levels, solutions] if command == "import" => Ok(Command::Import {
+            levels: PathBuf::from(levels),
+            solutions: PathBuf::from(solutions),
+        }),
+        [command, options @ ..] if command == "generate" => parse_generate(options),
+        [command, files @ ..] if command == "dedup" && !files.is_empty() => {
+            Ok(Command::Dedup {
+                files: files.iter().map(PathBuf::from).collect(),
+            })
+        }
+        _ => Err(copy_text::USAGE.to_string()),
+    }
+}
+
+/// The options `render` and `record` share, `--speed` being for `record` alone
+fn parse_drawing(
+    command: &str,
+    file: &str,
+    options: &[String],
+) -> Result<Command, String> {
+    let mut level = 1;
+    let mut moves = String::new();
+    let mut zoom = Zoom::Close;
+    let mut speed = None;
+    let mut output = None;
+    for pair in options.chunks(2) {
+        let [option, value] = pair else {
+            return Err(format!("{} needs a value", pair[0]));
+        };
+        match option.as_str() {
+            "--level" => level = parse_level(value)?,
+            "--moves" => moves = value.clone(),
+            "--zoom" => zoom = parse_zoom(value)?,
+            "--speed" if command == "record" => speed = Some(parse_speed(value)?),
+            "--output" | "-o" => output = Some(PathBuf::from(value)),
+            _ => return Err(copy_text::USAGE.to_string()),
+        }
+    }
+    let file = PathBuf::from(file);
+    if command == "render" {
+        return Ok(Command::Render {
+            file,
+            level,
+            moves,
+            zoom,
+            output,
+        });
+    }
+    if moves.is_empty() {
+        return Err("record needs the --moves to play".to_string());
+    }
+    Ok(Command::Record {
+        file,
+        level,
+        moves,
+        zoom,
+        speed: speed.unwrap_or(DEFAULT_SPEED),
+        output,
+    })
+}
+
+fn parse_generate(options: &[String]) -> Result<Command, String> {
+    let mut settings = Settings {
+        width: 9,
+        height: 8,
+        boxes: 3,
+        count: 10,
+        seed: generator::random_seed(),
+    };
+    let mut output = None;
+    for pair in options.chunks(2) {
+        let [option, value] = pair else {
+            return Err(format!("{} needs a value", pair[0]));
+        };
+        match option.as_str() {
+            "--width" => settings.width = parse_number(value, "width", 5, 20)?,
+            "--height" => settings.height = parse_number(value, "height", 5, 20)?,
+            "--boxes" => settings.boxes = parse_number(value, "box count", 1, 6)?,
+            "--count" => settings.count = parse_number(value, "level count", 1, 100)?,
+            "--seed" => {
+                settings.seed = value
+                    .parse()
+                    .map_err(|_| format!("'{}' is not a seed, try a number", value))?
+            }
+            "--output" | "-o" => output = Some(PathBuf::from(value)),
+            _ => return Err(copy_text::USAGE.to_string()),
+        }
+    }
+    Ok(Command::Generate { settings, output })
+}
+
+fn parse_number(
+    value: &str,
+    what: &str,
+    min: usize,
+    max: usize,
+) -> Result<usize, String> {
+    match value.parse::<usize>() {
+        Ok(number) if (min..=max).contains(&number) => Ok(number),
+        _ => Err(format!(
+            "'{}' is not a {}, try {} to {}",
+            value, what, min, max
+        )),
+    }
+}
+
+/// Moves a second when recording, if `--speed` isn't given
+const DEFAULT_SPEED: u64 = 4;
+
+fn parse_speed(speed: &str) -> Result<u64, String> {
+    match speed.parse::<u64>() {
+        Ok(speed) if (1..=100).contains(&speed) => Ok(speed),
+        _ => Err(format!(
+            "'{}' is not a speed, try 1 to 100 moves a second",
+            speed
+        )),
+    }
+}
+
+fn parse_zoom(zoom: &str) -> Result<Zoom, String> {
+    match zoom {
+        "close" => Ok(Zoom::Close),
+        "middle" => Ok(Zoom::Middle),
+        "far" => Ok(Zoom::Far),
+        _ => Err(format!(
+            "'{}' is not a zoom, try close, middle or far",
+            zoom
+        )),
+    }
+}
+
+fn parse_level(level: &str) -> Result<usize, String> {
+    match level.parse::<usize>() {
+        Ok(number) if number > 0 => Ok(number),
+        _ => Err(format!("'{}' is not a level number", level)),
+    }
+}
@@ -0,0 +1,239 @@
+#[cfg(test)]
+mod tests {
+    use std::path::PathBuf;
+
+    use crate::cli::{parse, Command};
+    use crate::copy_text;
+    use crate::generator::Settings;
+    use crate::types::Zoom;
+
+    fn command(line: &str) -> Result<Command, String> {
+        let args: Vec<String> = line.split_whitespace().map(String::from).collect();
+        parse(&args).map(|args| args.command)
+    }
+
+    fn settings(line: &str) -> Result<(Settings, Option<PathBuf>), String> {
+        match command(line)? {
+            Command::Generate { settings, output } => Ok((settings, output)),
+            other => panic!("{:?} isn't generate", other),
+        }
+    }
+
+    #[test]
+    fn test_no_arguments_plays() {
+        let args = parse(&[]).unwrap();
+        assert_eq!(args.command, Command::Play);
+        assert!(!args.develop);
+    }
+
+    #[test]
+    fn test_develop_goes_anywhere() {
+        let args =
+            parse(&["replay".into(), "--develop".into(), "2".into(), "rU".into()]);
+        let args = args.unwrap();
+        assert!(args.develop);
+        assert_eq!(
+            args.command,
+            Command::Replay {
+                level: 2,
+                solution: "rU".to_string()
+            }
+        );
+    }
+
+    #[test]
+    fn test_replay() {
+        // A solution split by spaces is put back together
+        assert_eq!(
+            command("replay 3 rr UU"),
+            Ok(Command::Replay {
+                level: 3,
+                solution: "rrUU".to_string()
+            })
+        );
+        assert!(command("replay 0 r").is_err());
+        assert_eq!(command("replay 3"), Err(copy_text::USAGE.to_string()));
+    }
+
+    #[test]
+    fn test_render_defaults() {
+        assert_eq!(
+            command("render levels.ban"),
+            Ok(Command::Render {
+                file: PathBuf::from("levels.ban"),
+                level: 1,
+                moves: String::new(),
+                zoom: Zoom::Close,
+                output: None,
+            })
+        );
+    }
+
+    #[test]
+    fn test_render_options() {
+        assert_eq!(
+            command("render levels.ban --level 3 --moves rrU --zoom far -o level3.png"),
+            Ok(Command::Render {
+                file: PathBuf::from("levels.ban"),
+                level: 3,
+                moves: "rrU".to_string(),
+                zoom: Zoom::Far,
+                output: Some(PathBuf::from("level3.png")),
+            })
+        );
+        assert!(command("render levels.ban --level 0").is_err());
+        assert!(command("render levels.ban --zoom huge").is_err());
+    }
+
+    #[test]
+    fn test_render_option_errors() {
+        assert_eq!(
+            command("render levels.ban --level"),
+            Err("--level needs a value".to_string())
+        );
+        assert_eq!(
+            command("render levels.ban --colour red"),
+            Err(copy_text::USAGE.to_string())
+        );
+        // Only a recording has a speed
+        assert_eq!(
+            command("render levels.ban --speed 8"),
+            Err(copy_text::USAGE.to_string())
+        );
+        assert_eq!(command("render"), Err(copy_text::USAGE.to_string()));
+    }
+
+    #[test]
+    fn test_record_defaults() {
+        assert_eq!(
+            command("record levels.ban --moves rrU"),
+            Ok(Command::Record {
+                file: PathBuf::from("levels.ban"),
+                level: 1,
+                moves: "rrU".to_string(),
+                zoom: Zoom::Close,
+                speed: 4,
+                output: None,
+            })
+        );
+    }
+
+    #[test]
+    fn test_record_needs_moves_and_a_sensible_speed() {
+        assert!(command("record levels.ban").is_err());
+        assert!(command("record levels.ban --moves r --speed 0").is_err());
+        assert!(command("record levels.ban --moves r --speed 101").is_err());
+        assert_eq!(
+            command("record levels.ban --moves r --speed"),
+            Err("--speed needs a value".to_string())
+        );
+        match command("record levels.ban --moves r --speed 100 --output solve.gif") {
+            Ok(Command::Record { speed, output, .. }) => {
+                assert_eq!(speed, 100);
+                assert_eq!(output, Some(PathBuf::from("solve.gif")));
+            }
+            other => panic!("{:?} isn't record", other),
+        }
+    }
+
+    #[test]
+    fn test_import() {
+        assert_eq!(
+            command("import levels.ban solutions.txt"),
+            Ok(Command::Import {
+                levels: PathBuf::from("levels.ban"),
+                solutions: PathBuf::from("solutions.txt"),
+            })
+        );
+        assert_eq!(
+            command("import levels.ban"),
+            Err(copy_text::USAGE.to_string())
+        );
+        assert_eq!(
+            command("import levels.ban solutions.txt more.txt"),
+            Err(copy_text::USAGE.to_string())
+        );
+    }
+
+    #[test]
+    fn test_dedup() {
+        assert_eq!(
+            command("dedup a.ban b.ban"),
+            Ok(Command::Dedup {
+                files: vec![PathBuf::from("a.ban"), PathBuf::from("b.ban")],
+            })
+        );
+        assert_eq!(command("dedup"), Err(copy_text::USAGE.to_string()));
+    }
+
+    #[test]
+    fn test_generate_defaults() {
+        let (settings, output) = settings("generate").unwrap();
+        assert_eq!(
+            (
+                settings.width,
+                settings.height,
+                settings.boxes,
+                settings.count
+            ),
+            (9, 8, 3, 10)
+        );
+        assert_eq!(output, None);
+    }
+
+    #[test]
+    fn test_generate_options() {
+        let (settings, output) = settings(
+            "generate --width 20 --height 5 --boxes 6 --count 1 --seed 42 -o mine.ban",
+        )
+        .unwrap();
+        assert_eq!(
+            settings,
+            Settings {
+                width: 20,
+                height: 5,
+                boxes: 6,
+                count: 1,
+                seed: 42,
+            }
+        );
+        assert_eq!(output, Some(PathBuf::from("mine.ban")));
+    }
+
+    #[test]
+    fn test_generate_bounds() {
+        assert_eq!(
+            settings("generate --width 4"),
+            Err("'4' is not a width, try 5 to 20".to_string())
+        );
+        assert!(settings("generate --width 21").is_err());
+        assert!(settings("generate --height 4").is_err());
+        assert!(settings("generate --height 21").is_err());
+        assert_eq!(
+            settings("generate --boxes 7"),
+            Err("'7' is not a box count, try 1 to 6".to_string())
+        );
+        assert!(settings("generate --boxes 0").is_err());
+        assert!(settings("generate --count 0").is_err());
+        assert!(settings("generate --count 101").is_err());
+        assert!(settings("generate --width wide").is_err());
+        assert!(settings("generate --seed -1").is_err());
+    }
+
+    #[test]
+    fn test_generate_option_errors() {
+        assert_eq!(
+            settings("generate --boxes"),
+            Err("--boxes needs a value".to_string())
+        );
+        assert_eq!(
+            settings("generate --walls 3"),
+            Err(copy_text::USAGE.to_string())
+        );
+    }
+
+    #[test]
+    fn test_unknown_command() {
+        assert_eq!(command("solve"), Err(copy_text::USAGE.to_string()));
+    }
+}
@@ -0,0 +1,67 @@
+/*!
+ * Description:
+ *
+ * Copies text to the clipboard of whatever terminal termban is showing in, using the
+ * OSC 52 escape sequence. The terminal does the copying, so it works over SSH and
+ * without a clipboard tool installed on the machine running the game.
+ *
+ * Usage:
+ *
+ *   clipboard::copy(&soko_loader::to_xsb(&world))?;
+ *
+ * Notes:
+ *
+ * Not every terminal supports OSC 52, and some turn it off by default. There's no way to
+ * ask whether the copy worked, the terminal just ignores a sequence it doesn't accept.
+ *
+ * Inside tmux the sequence is wrapped so tmux passes it on to the outer terminal, which
+ * needs `set -g allow-passthrough on` in tmux 3.3 and later.
+ */
+
+use std::env;
+use std::io::{self, Write};
+
+const BASE64: &[u8; 64] =
+    b"ABCDEFGHIJKLMNOPQRSTUVWXYZabcdefghijklmnopqrstuvwxyz0123456789+/";
+
+/// Puts `text` on the terminal's clipboard
+pub fn copy(text: &str) -> io::Result<()> {
+    let mut sequence = osc52(text);
+    if env::var_os("TMUX").is_some() {
+        sequence = tmux_passthrough(&sequence);
+    }
+    let mut stdout = io::stdout();
+    stdout.write_all(sequence.as_bytes())?;
+    stdout.flush()
+}
+
+/// The escape sequence that sets the clipboard ("c") to `text`
+pub fn osc52(text: &str) -> String {
+    format!("\x1b]52;c;{}\x07", base64(text.as_bytes()))
+}
+
+/// Wraps an escape sequence for tmux to hand to the terminal it's running in
+pub fn tmux_passthrough(sequence: &str) -> String {
+    format!("\x1bPtmux;{}\x1b\\", sequence.replace('\x1b', "\x1b\x1b"))
+}
+
+/// Standard base64, padded with `=`
+pub fn base64(bytes: &[u8]) -> String {
+    let mut encoded = String::with_capacity(bytes.len().div_ceil(3) * 4);
+    for chunk in bytes.chunks(3) {
+        let b = [
+            chunk[0],
+            *chunk.get(1).unwrap_or(&0),
+            *chunk.get(2).unwrap_or(&0),
+        ];
+        let n = u32::from(b[0]) << 16 | u32::from(b[1]) << 8 | u32::from(b[2]);
+        for i in 0..4 {
+            if i <= chunk.len() {
+                encoded.push(BASE64[(n >> (18 - 6 * i) & 0x3f) as usize] as char);
+            } else {
+                encoded.push('=');
+            }
+        }
+    }
+    encoded
+}
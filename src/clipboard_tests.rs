@@ -0,0 +1,27 @@
+#[cfg(test)]
+mod tests {
+    use crate::clipboard::*;
+
+    #[test]
+    fn test_base64_pads_to_whole_groups() {
+        assert_eq!(base64(b""), "");
+        assert_eq!(base64(b"f"), "Zg==");
+        assert_eq!(base64(b"fo"), "Zm8=");
+        assert_eq!(base64(b"foo"), "Zm9v");
+        assert_eq!(base64(b"foobar"), "Zm9vYmFy");
+        assert_eq!(base64(&[0xff, 0xfe]), "//4=");
+    }
+
+    #[test]
+    fn test_osc52_sets_the_clipboard() {
+        assert_eq!(osc52("#@$.#\n"), "\x1b]52;c;I0AkLiMK\x07");
+    }
+
+    #[test]
+    fn test_tmux_passthrough_doubles_escapes() {
+        assert_eq!(
+            tmux_passthrough("\x1b]52;c;Zg==\x07"),
+            "\x1bPtmux;\x1b\x1b]52;c;Zg==\x07\x1b\\"
+        );
+    }
+}
@@ -0,0 +1,151 @@
+/*!
+ * Description:
+ *
+ * Level collections: a `.ban` file of levels along with the identity that saves record
+ * progress against.
+ *
+ * A collection is known by its file name alone, so progress stays with a file however
+ * it's edited. Each level in it is known by a hash of its starting layout rather than by
+ * its position, so levels can be reordered, retitled or added to the file without
+ * progress sticking to the wrong level. The hash of the whole file only tells the game
+ * when a file it has open has changed on disk.
+ *
+ * Two files with the same name would share progress, so only one of them is loaded: a
+ * file in the player's levels directory takes the place of a bundled one.
+ *
+ * A file can start with `Title:` and `Author:` lines to name the collection and whoever
+ * made it.
+ *
+ * Usage:
+ *
+ *   let (collection, worlds) = collection::load(Path::new("resources/levels/micro2.ban"))?;
+ *   let (files, problems) = collection::scan(&collection::level_dirs());
+ */
+
+use std::fs;
+use std::path::{Path, PathBuf};
+
+use crate::soko_loader::{parse_header, parse_sokoban_worlds, to_xsb};
+use crate::storage::{self, read_file};
+use crate::types::{Collection, CollectionFile, World};
+
+/// The collection to open when the save doesn't say which
+pub const DEFAULT: &str = "micro2";
+
+/// What levels pasted into the game are kept under, they don't come from a file
+pub const PASTED: &str = "pasted";
+
+/// The file extensions `scan` picks up as level files
+const EXTENSIONS: [&str; 3] = ["ban", "xsb", "txt"];
+
+/// Where to look for level files: the ones that ship with the game, then the player's
+/// own in the data directory
+pub fn level_dirs() -> Vec<PathBuf> {
+    vec![
+        PathBuf::from("./resources/levels"),
+        storage::data_dir().join("levels"),
+    ]
+}
+
+/// Loads every level file in `dirs`, in file name order. Directories that don't exist are
+/// passed over, files that don't load are listed in the second half of the result.
+pub fn scan(dirs: &[PathBuf]) -> (Vec<CollectionFile>, Vec<String>) {
+    let mut files: Vec<CollectionFile> = Vec::new();
+    // Where each of `files` was loaded from
+    let mut loaded: Vec<PathBuf> = Vec::new();
+    let mut problems = Vec::new();
+    for dir in dirs {
+        let Ok(entries) = fs::read_dir(dir) else {
+            continue;
+        };
+        let mut paths: Vec<PathBuf> = entries
+            .filter_map(|entry| entry.ok().map(|entry| entry.path()))
+            .filter(|path| {
+                path.extension()
+                    .is_some_and(|ext| EXTENSIONS.iter().any(|known| ext == *known))
+            })
+            .collect();
+        paths.sort();
+
+        for path in paths {
+            let file = match load(&path) {
+                Ok((collection, worlds)) => CollectionFile { collection, worlds },
+                Err(e) => {
+                    problems.push(e);
+                    continue;
+                }
+            };
+            // A later directory wins, which is where the player's own levels are
+            let same_name = files
+                .iter()
+                .position(|found| found.collection.name == file.collection.name);
+            if let Some(index) = same_name {
+                problems.push(format!(
+                    "{} is hidden by {}, which has the same name",
+                    loaded[index].display(),
+                    path.display()
+                ));
+                files[index] = file;
+                loaded[index] = path;
+            } else {
+                files.push(file);
+                loaded.push(path);
+            }
+        }
+    }
+    (files, problems)
+}
+
+/// Loads a collection of levels from a file
+pub fn load(path: &Path) -> Result<(Collection, Vec<World>), String> {
+    let contents = read_file(path)
+        .map_err(|e| format!("Couldn't read {}: {}", path.display(), e))?;
+    from_contents(&name_of(path), &contents)
+        .map_err(|e| format!("{}: {}", path.display(), e))
+}
+
+/// What the collection in the level file at `path` is known by
+pub fn name_of(path: &Path) -> String {
+    path.file_stem()
+        .map(|stem| stem.to_string_lossy().into_owned())
+        .unwrap_or_default()
+}
+
+/// Parses a collection called `name` from the text of a level file
+pub fn from_contents(
+    name: &str,
+    contents: &str,
+) -> Result<(Collection, Vec<World>), String> {
+    let worlds = parse_sokoban_worlds(contents)?;
+    let header = parse_header(contents);
+    let field = |key: &str| {
+        header
+            .iter()
+            .find(|(found, _)| found.eq_ignore_ascii_case(key))
+            .map(|(_, value)| value.clone())
+    };
+    let collection = Collection {
+        name: name.to_string(),
+        title: field("Title").unwrap_or_else(|| name.to_string()),
+        author: field("Author"),
+        hash: content_hash(contents),
+        level_ids: worlds.iter().map(level_id).collect(),
+    };
+    Ok((collection, worlds))
+}
+
+pub fn content_hash(contents: &str) -> String {
+    format!("{:016x}", fnv1a(contents.as_bytes()))
+}
+
+/// Identifies a level by its layout alone, its title doesn't count
+pub fn level_id(world: &World) -> String {
+    content_hash(&to_xsb(world))
+}
+
+/// 64 bit FNV-1a, stable from run to run and build to build unlike `DefaultHasher`
+fn fnv1a(bytes: &[u8]) -> u64 {
+    bytes.iter().fold(0xcbf2_9ce4_8422_2325, |hash, &byte| {
+        (hash ^ u64::from(byte)).wrapping_mul(0x0100_0000_01b3)
+    })
+}
@@ -0,0 +1,144 @@
+use std::io;
+use std::time::Duration;
+
+use crate::collection;
+use crate::copy_text;
+use crate::keymap::{Action, Screen};
+use crate::types::{CollectionSelectAction, Model, RunningState};
+use ratatui::prelude::*;
+use ratatui::{
+    crossterm::event::{self, Event},
+    widgets::{Block, List, ListItem, Paragraph},
+    Frame,
+};
+
+pub fn view(model: &mut Model, frame: &mut Frame) {
+    let header_height = copy_text::COLLECTION_SELECT.lines().count() as u16;
+    let [header_area, list_area, prompt_area] = Layout::vertical([
+        Constraint::Length(header_height),
+        Constraint::Min(0),
+        Constraint::Length(1),
+    ])
+    .areas(frame.area());
+
+    frame.render_widget(Paragraph::new(copy_text::COLLECTION_SELECT), header_area);
+
+    let save = model.save_file.current();
+    let items: Vec<ListItem> = model
+        .collection_select
+        .files
+        .iter()
+        .map(|file| {
+            let collection = &file.collection;
+            let levels = file.worlds.len();
+            let percent = save.solved_count(collection) * 100 / levels.max(1);
+            ListItem::new(format!(
+                "{:<32} {:<24} {:>4} levels  {:>3}%",
+                collection.title,
+                collection.author.as_deref().unwrap_or("Unknown"),
+                levels,
+                percent,
+            ))
+        })
+        .collect();
+
+    let list = List::new(items)
+        .block(Block::bordered().title("COLLECTIONS"))
+        .highlight_style(Style::default().add_modifier(Modifier::REVERSED))
+        .highlight_symbol("» ");
+    frame.render_stateful_widget(
+        list,
+        list_area,
+        &mut model.collection_select.list_state,
+    );
+
+    let keys = |action| model.keymap.key(Screen::Collections, action);
+    let mut prompt = format!(
+        " {}{} Choose   {} Open   {} Back",
+        keys(Action::Up),
+        keys(Action::Down),
+        keys(Action::Open),
+        keys(Action::Back)
+    );
+    let problems = &model.collection_select.problems;
+    if !problems.is_empty() {
+        prompt.push_str(&format!(
+            "   ({} file(s) couldn't be loaded: {})",
+            problems.len(),
+            problems.join("; ")
+        ));
+    }
+    frame.render_widget(Paragraph::new(prompt), prompt_area);
+}
+
+/// Looks for level files again, so ones added while the game is running show up, and
+/// puts the cursor on the collection that's open
+pub fn refresh(model: &mut Model) {
+    let (files, problems) = collection::scan(&collection::level_dirs());
+    let open = files
+        .iter()
+        .position(|file| file.collection.name == model.game.collection.name)
+        .unwrap_or(0);
+    let select = &mut model.collection_select;
+    select.files = files;
+    select.problems = problems;
+    select.list_state.select(Some(open));
+}
+
+pub fn update(
+    model: &mut Model,
+    msg: CollectionSelectAction,
+) -> Option<CollectionSelectAction> {
+    let select = &mut model.collection_select;
+    let last = select.files.len().saturating_sub(1);
+    let selected = select.list_state.selected().unwrap_or(0).min(last);
+    match msg {
+        CollectionSelectAction::Back => model.running_state = RunningState::Menu,
+        CollectionSelectAction::Up => {
+            select.list_state.select(Some(selected.saturating_sub(1)))
+        }
+        CollectionSelectAction::Down => {
+            select.list_state.select(Some((selected + 1).min(last)))
+        }
+        CollectionSelectAction::First => select.list_state.select(Some(0)),
+        CollectionSelectAction::Last => select.list_state.select(Some(last)),
+        CollectionSelectAction::Open => {
+            let file = select.files.get(selected)?;
+            let open = &model.game.collection;
+            if file.collection.name != open.name || file.collection.hash != open.hash {
+                let level =
+                    model.save_file.current().first_unfinished(&file.collection);
+                model.game.open_collection(file, level);
+                model.save_file.collection = file.collection.name.clone();
+            }
+            model.running_state = RunningState::LevelSelect;
+        }
+    };
+    None
+}
+
+pub fn handle_event(model: &Model) -> io::Result<Option<CollectionSelectAction>> {
+    if event::poll(Duration::from_millis(250))? {
+        if let Event::Key(key) = event::read()? {
+            if key.kind == event::KeyEventKind::Press {
+                return Ok(handle_key(model, key));
+            }
+        }
+    }
+    Ok(None)
+}
+
+pub fn handle_key(
+    model: &Model,
+    key: event::KeyEvent,
+) -> Option<CollectionSelectAction> {
+    match model.keymap.action(Screen::Collections, &key)? {
+        Action::Up => Some(CollectionSelectAction::Up),
+        Action::Down => Some(CollectionSelectAction::Down),
+        Action::First => Some(CollectionSelectAction::First),
+        Action::Last => Some(CollectionSelectAction::Last),
+        Action::Open => Some(CollectionSelectAction::Open),
+        Action::Back => Some(CollectionSelectAction::Back),
+        _ => None,
+    }
+}
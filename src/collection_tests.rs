@@ -0,0 +1,195 @@
+#[cfg(test)]
+mod tests {
+    use crate::collection::*;
+    use crate::types::{Collection, Save, SaveFile, Totals, Unlock};
+
+    const LEVELS: &str = "; 1
+
+#####
+#@$.#
+#####
+
+; 2
+
+######
+#@ $.#
+######
+";
+
+    const REORDERED: &str = "; Extra
+
+#######
+#@ $ .#
+#######
+
+; Two
+
+######
+#@ $.#
+######
+
+; One
+
+#####
+#@$.#
+#####
+";
+
+    #[test]
+    fn test_level_ids_ignore_titles() {
+        let (first, _) = from_contents("micro", LEVELS).unwrap();
+        let (second, _) = from_contents("micro", REORDERED).unwrap();
+
+        assert_eq!(first.level_ids[0], second.level_ids[2]);
+        assert_eq!(first.level_ids[1], second.level_ids[1]);
+        assert_ne!(first.level_ids[0], first.level_ids[1]);
+        assert_ne!(first.hash, second.hash);
+    }
+
+    #[test]
+    fn test_progress_follows_reordered_levels() {
+        let (before, _) = from_contents("micro", LEVELS).unwrap();
+        let mut save_file = SaveFile::new();
+        let save = save_file.current_mut();
+        save.level_mut(&before, 0).solved = true;
+        save.level_mut(&before, 1).in_progress = Some("r".to_string());
+
+        let (after, _) = from_contents("micro", REORDERED).unwrap();
+        let save = save_file.current();
+        assert!(save.is_solved(&after, 2));
+        assert!(!save.is_solved(&after, 0));
+        let two = save.level(&after, 1).unwrap();
+        assert_eq!(two.in_progress.as_deref(), Some("r"));
+    }
+
+    #[test]
+    fn test_collections_keep_separate_progress() {
+        let (micro, _) = from_contents("micro", LEVELS).unwrap();
+        let (micro2, _) = from_contents("micro2", LEVELS).unwrap();
+        let mut save_file = SaveFile::new();
+        save_file.current_mut().level_mut(&micro, 0).solved = true;
+
+        assert_eq!(save_file.current().first_unfinished(&micro), 1);
+        assert_eq!(save_file.current().first_unfinished(&micro2), 0);
+    }
+
+    #[test]
+    fn test_unlock_rules() {
+        let collection = Collection {
+            name: "test".to_string(),
+            level_ids: (0..6).map(|index| index.to_string()).collect(),
+            ..Collection::default()
+        };
+        let mut save_file = SaveFile::new();
+        let save = save_file.current_mut();
+        save.level_mut(&collection, 0).solved = true;
+        save.level_mut(&collection, 2).solved = true;
+        let unlocked = |save: &Save, unlock| {
+            (0..6)
+                .filter(|&index| save.is_unlocked(&collection, unlock, index))
+                .count()
+        };
+
+        // Level 1 is unfinished, so only it and the one before are open
+        assert_eq!(unlocked(save_file.current(), Unlock::Linear), 2);
+        // Levels 1 and 3 are open, level 4 would be the third unfinished one
+        assert_eq!(unlocked(save_file.current(), Unlock::Window(2)), 4);
+        assert_eq!(unlocked(save_file.current(), Unlock::Open), 6);
+
+        // Skipping level 1 lets the player carry on past it
+        save_file.current_mut().level_mut(&collection, 1).skipped = true;
+        assert_eq!(unlocked(save_file.current(), Unlock::Linear), 4);
+        assert!(!save_file.current().is_solved(&collection, 1));
+    }
+
+    #[test]
+    fn test_totals_add_up_the_best_solutions() {
+        let (collection, _) = from_contents("micro", REORDERED).unwrap();
+        let mut save_file = SaveFile::new();
+        let save = save_file.current_mut();
+        let level = save.level_mut(&collection, 0);
+        level.solved = true;
+        level.solution = Some("rRR".to_string());
+        let level = save.level_mut(&collection, 2);
+        level.solved = true;
+        level.solution = Some("R".to_string());
+        save.level_mut(&collection, 1).skipped = true;
+
+        assert_eq!(
+            save_file.current().totals(&collection),
+            Totals {
+                levels: 3,
+                solved: 2,
+                skipped: 1,
+                moves: 4,
+                pushes: 3,
+            }
+        );
+    }
+
+    #[test]
+    fn test_header_names_the_collection() {
+        let contents = format!("Title: Tiny Boxes\nAuthor: Someone\n\n{}", LEVELS);
+        let (collection, worlds) = from_contents("tiny", &contents).unwrap();
+
+        assert_eq!(collection.title, "Tiny Boxes");
+        assert_eq!(collection.author.as_deref(), Some("Someone"));
+        assert_eq!(worlds.len(), 2);
+
+        // Without a header the file name stands in
+        let (collection, _) = from_contents("tiny", LEVELS).unwrap();
+        assert_eq!(collection.title, "tiny");
+        assert_eq!(collection.author, None);
+    }
+
+    #[test]
+    fn test_scan_finds_level_files() {
+        let dir =
+            std::env::temp_dir().join(format!("termban-scan-{}", std::process::id()));
+        let _ = std::fs::remove_dir_all(&dir);
+        std::fs::create_dir_all(&dir).unwrap();
+        std::fs::write(dir.join("b.ban"), LEVELS).unwrap();
+        std::fs::write(dir.join("a.xsb"), REORDERED).unwrap();
+        std::fs::write(dir.join("broken.ban"), "not a level").unwrap();
+        std::fs::write(dir.join("notes.md"), "# Not levels").unwrap();
+
+        let missing = dir.join("missing");
+        let (files, problems) = scan(&[dir.clone(), missing]);
+        let names: Vec<&str> = files
+            .iter()
+            .map(|file| file.collection.name.as_str())
+            .collect();
+        assert_eq!(names, vec!["a", "b"]);
+        assert_eq!(files[0].worlds.len(), 3);
+        assert_eq!(problems.len(), 1);
+        assert!(problems[0].contains("broken.ban"));
+
+        std::fs::remove_dir_all(dir).unwrap();
+    }
+
+    #[test]
+    fn test_own_levels_hide_bundled_ones_of_the_same_name() {
+        let root =
+            std::env::temp_dir().join(format!("termban-shadow-{}", std::process::id()));
+        let _ = std::fs::remove_dir_all(&root);
+        let (bundled, own) = (root.join("bundled"), root.join("own"));
+        std::fs::create_dir_all(&bundled).unwrap();
+        std::fs::create_dir_all(&own).unwrap();
+        std::fs::write(bundled.join("micro.ban"), LEVELS).unwrap();
+        std::fs::write(bundled.join("other.ban"), LEVELS).unwrap();
+        std::fs::write(own.join("micro.ban"), REORDERED).unwrap();
+
+        let (files, problems) = scan(&[bundled, own.clone()]);
+        let names: Vec<&str> = files
+            .iter()
+            .map(|file| file.collection.name.as_str())
+            .collect();
+        assert_eq!(names, vec!["micro", "other"]);
+        let (own_micro, _) = load(&own.join("micro.ban")).unwrap();
+        assert_eq!(files[0].collection.hash, own_micro.hash);
+        assert_eq!(problems.len(), 1);
+        assert!(problems[0].contains("hidden"));
+
+        std::fs::remove_dir_all(root).unwrap();
+    }
+}
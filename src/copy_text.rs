@@ -17,14 +17,10 @@ TERMBAN SOKOBAN 1.0 ENGINE
 ==========================
 INTRODUCING... MICROBAN I!
         BY DAVID W. SKINNER
+";
 
-CONTROLS:       Press Enter to Begin
-                Press Escape to Leave
-                Move Player Using WASD/Arrows
-                Press R to Restart Level
-                Press Z or U to Undo a Move
-
-RULES:
+/// Goes under the controls, which are written out from the key bindings
+pub const RULES: &str = "RULES:
     1. The PLAYER and BOXES can only occupy EMPTY or GOAL Tiles.
     2. The PLAYER can push a BOX onto an EMPTY Tile.
     3. A BOX cannot be pushed by another BOX.
@@ -32,8 +28,45 @@ GOAL:
     The puzzle is solved when every GOAL tile is occupied by a BOX.
 ";
 
+pub const COLLECTION_SELECT: &str = "
+COLLECTIONS
+===========
+
+";
+
 pub const LEVEL_SELECT: &str = "
-LEVEL SELECT (MICROBAN I)
-=========================
+LEVEL SELECT
+============
 
 ";
+
+pub const USAGE: &str = "Usage:
+    termban                            Play!
+    termban replay <level> <solution>  Watch a LURD solution to a level
+    termban render <file>              Draw a level of a level file
+        --level <n>                    Which level, the first if not given
+        --moves <solution>             Play these LURD moves first
+        --zoom <close|middle|far>      How big to draw it, close if not given
+        --output <file>                Write a .png, .svg or ANSI text file, rather
+                                       than printing ANSI text
+    termban record <file>              Record a solution being played
+        --moves <solution>             The LURD moves to play
+        --level, --zoom                As for render
+        --speed <n>                    Moves a second, 4 if not given
+        --output <file>                Write a .gif, or an asciicast for anything
+                                       else, rather than printing the asciicast
+    termban import <file> <solutions>  Check the solutions in a solution file
+                                       against a level file's levels, and save
+                                       the ones that work
+    termban dedup <files...>           List levels that are in the files more
+                                       than once, even turned or mirrored
+    termban generate                   Make up new levels, then play them
+        --width <n>, --height <n>      The room's size, 9 by 8 if not given
+        --boxes <n>                    Boxes in each level, 3 if not given
+        --count <n>                    How many levels, 10 if not given
+        --seed <n>                     Make the same levels as last time
+        --output <file>                Write the .ban file here, rather than
+                                       to the levels directory
+
+Options:
+    --develop                          Open every level";
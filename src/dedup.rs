@@ -0,0 +1,133 @@
+/*!
+ * Description:
+ *
+ * Finds the same level turning up more than once across level files, as happens when
+ * community packs are merged: often under another title, and sometimes turned or
+ * mirrored.
+ *
+ * Usage:
+ *
+ *   termban dedup micro.ban micro2.ban community.xsb
+ *
+ * Notes:
+ *
+ * Each level is boiled down to a key that's the same for every copy of it. Floor the
+ * player can never get to is walled in, since it makes no difference to the puzzle.
+ * Then the key is a hash of the level's canonical layout. Titles and `Key: value` lines
+ * don't count.
+ */
+
+use std::collections::{HashMap, VecDeque};
+use std::path::PathBuf;
+
+use crate::collection::{self, content_hash};
+use crate::soko_game::get_new_position;
+use crate::soko_loader::to_xsb;
+use crate::types::{Direction, Entity, Tile, World};
+
+/// A level and where it came from
+pub struct Found {
+    pub file: PathBuf,
+    /// 1 based, as the level would be picked in the game
+    pub number: usize,
+    pub world: World,
+}
+
+/// Runs `termban dedup`: loads every level in `files` and says which are the same
+pub fn run(files: &[PathBuf]) -> Result<String, String> {
+    let mut levels = Vec::new();
+    for file in files {
+        let (_, worlds) = collection::load(file)?;
+        levels.extend(worlds.into_iter().enumerate().map(|(index, world)| Found {
+            file: file.clone(),
+            number: index + 1,
+            world,
+        }));
+    }
+    Ok(report(&levels, &duplicates(&levels)))
+}
+
+/// The levels that are the same as another, in groups, each in the order they were
+/// found and the groups in order of their first level
+pub fn duplicates(levels: &[Found]) -> Vec<Vec<usize>> {
+    let mut groups: Vec<Vec<usize>> = Vec::new();
+    let mut by_key: HashMap<String, usize> = HashMap::new();
+    for (index, level) in levels.iter().enumerate() {
+        let group = *by_key.entry(key(&level.world)).or_insert_with(|| {
+            groups.push(Vec::new());
+            groups.len() - 1
+        });
+        groups[group].push(index);
+    }
+    groups.retain(|group| group.len() > 1);
+    groups
+}
+
+fn report(levels: &[Found], groups: &[Vec<usize>]) -> String {
+    if groups.is_empty() {
+        return format!("No duplicates among {} levels\n", levels.len());
+    }
+    let mut text = String::new();
+    for group in groups {
+        text.push_str("Same level:\n");
+        for &index in group {
+            let level = &levels[index];
+            text.push_str(&format!(
+                "    {} level {} ({})\n",
+                level.file.display(),
+                level.number,
+                level.world.name
+            ));
+        }
+    }
+    let copies: usize = groups.iter().map(|group| group.len() - 1).sum();
+    let plural = if copies == 1 { "" } else { "s" };
+    text.push_str(&format!(
+        "{} duplicate{} among {} levels\n",
+        copies,
+        plural,
+        levels.len()
+    ));
+    text
+}
+
+/// What every copy of `world` has in common, however it's turned
+pub fn key(world: &World) -> String {
+    content_hash(&to_xsb(&wall_in_unreachable(world).canonical()))
+}
+
+/// `world` with walls where there's floor the player can't ever reach. Boxes don't
+/// count as in the way, they could be moved.
+pub fn wall_in_unreachable(world: &World) -> World {
+    let mut world = world.clone();
+    let Some(start) = world.entities.iter().find_map(|entity| match entity {
+        Entity::Player(player) => Some(player.position.clone()),
+        Entity::SokoBox(_) => None,
+    }) else {
+        return world;
+    };
+
+    let mut reached = ndarray::Array2::from_elem(world.board.dim(), false);
+    reached[start.arr_index()] = true;
+    let mut queue = VecDeque::from([start]);
+    while let Some(position) = queue.pop_front() {
+        for direction in Direction::ALL {
+            let next = get_new_position(position.clone(), &direction);
+            let open = matches!(
+                world.board.get(next.arr_index()),
+                Some(Tile::Floor | Tile::Goal)
+            );
+            if open && !reached[next.arr_index()] {
+                reached[next.arr_index()] = true;
+                queue.push_back(next);
+            }
+        }
+    }
+
+    for ((y, x), tile) in world.board.indexed_iter_mut() {
+        if *tile == Tile::Floor && !reached[[y, x]] {
+            *tile = Tile::Wall;
+        }
+    }
+    world
+}
@@ -0,0 +1,70 @@
+#[cfg(test)]
+mod tests {
+    use std::path::PathBuf;
+
+    use crate::dedup::*;
+    use crate::soko_loader::to_xsb;
+    use crate::test_util::load_world;
+    use crate::transform::Symmetry;
+    use crate::types::World;
+
+    const LEVEL: &str = "; Original
+#####
+#@  #
+# $ ##
+#.   #
+######
+";
+
+    fn found(world: World, number: usize) -> Found {
+        Found {
+            file: PathBuf::from("test.ban"),
+            number,
+            world,
+        }
+    }
+
+    #[test]
+    fn test_same_key_however_its_turned() {
+        let world = load_world(LEVEL);
+        let original = key(&world);
+        for symmetry in Symmetry::ALL {
+            assert_eq!(
+                key(&world.transformed(symmetry)),
+                original,
+                "{:?}",
+                symmetry
+            );
+        }
+        // Moving the box makes it another level
+        let other = load_world("; Other\n#####\n#@$ #\n#   ##\n#.   #\n######\n");
+        assert_ne!(key(&other), original);
+    }
+
+    #[test]
+    fn test_unreachable_floor_is_ignored() {
+        let pocket = load_world("; Pocket\n#####\n#@$.#\n#####\n# # #\n#####\n");
+        assert_eq!(
+            to_xsb(&wall_in_unreachable(&pocket)),
+            "#####\n#@$.#\n#####\n#####\n#####\n"
+        );
+        // A pocket of floor the player can't get into is the same as solid wall
+        let solid = load_world("; Solid\n#####\n#@$.#\n#####\n#####\n#####\n");
+        assert_eq!(key(&solid), key(&pocket));
+    }
+
+    #[test]
+    fn test_duplicates_are_grouped_in_order() {
+        let world = load_world(LEVEL);
+        let other = load_world("; Other\n#####\n#@$.#\n#####\n");
+        let levels = vec![
+            found(world.clone(), 1),
+            found(other.clone(), 2),
+            found(world.transformed(Symmetry::MIRROR), 3),
+            found(other.rotated(1), 4),
+            found(world.rotated(2), 5),
+        ];
+        assert_eq!(duplicates(&levels), vec![vec![0, 2, 4], vec![1, 3]]);
+        assert!(duplicates(&levels[..2]).is_empty());
+    }
+}
@@ -0,0 +1,425 @@
+/*!
+ * Description:
+ *
+ * Makes up new levels, for practice once the bundled collections run out.
+ *
+ * A level starts as a random room with every box sitting on a goal, the way it looks
+ * once solved. The player then plays it backwards, pulling boxes about at random, and
+ * wherever that leaves them is the starting position. Every level made this way can be
+ * solved by pushing the boxes back, and the solver looks for a shorter way there. Of
+ * several tries the level that scores best is kept: a longer solution scores higher,
+ * and more so the more often it has to switch between boxes.
+ *
+ * Usage:
+ *
+ *   termban generate --width 9 --height 8 --boxes 3 --count 10
+ *
+ * Notes:
+ *
+ * The same seed always makes the same levels. Levels are written as a `.ban` file to
+ * the player's levels directory, where the collection browser picks them up.
+ *
+ * The solver gives up on big rooms with many boxes. Those levels are still made, but
+ * without a best solution to aim for.
+ */
+
+use std::collections::{BTreeSet, VecDeque};
+use std::path::{Path, PathBuf};
+use std::time::{SystemTime, UNIX_EPOCH};
+
+use ndarray::Array2;
+
+use crate::dedup::wall_in_unreachable;
+use crate::reverse::{apply_pull, player_position};
+use crate::soko_game::{apply_move, get_new_position, player_path};
+use crate::soko_loader::to_xsb;
+use crate::soko_state::splitmix64;
+use crate::types::{
+    Coordinate, Direction, Entity, MoveOutcome, Player, SokoBox, Tile, World,
+};
+use crate::{lurd, solver, storage};
+
+/// Rooms tried for each level, the best of them is kept
+const TRIES: usize = 8;
+
+/// Pulls made taking the boxes away from their goals
+const PULLS: usize = 80;
+
+/// Pulls between the positions along the way that are tried as the start
+const CHECKPOINT: usize = 20;
+
+/// How much of the inside of the room is carved out as floor, in percent
+const OPEN: usize = 55;
+
+/// Every solved position the solver may look at to check a level, enough for small rooms
+const SOLVER_LIMIT: usize = 100_000;
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct Settings {
+    /// The room's size, outer walls included
+    pub width: usize,
+    pub height: usize,
+    pub boxes: usize,
+    /// Levels to make
+    pub count: usize,
+    pub seed: u64,
+}
+
+/// A level that was made, with a solution to it
+pub struct Generated {
+    pub world: World,
+    pub solution: String,
+    /// Whether the solver found the solution, so none is shorter. Otherwise it's the
+    /// pulls pushed back, for levels too big for the solver.
+    pub optimal: bool,
+    pub score: usize,
+}
+
+/// A seed that's different every time, for when one isn't given
+pub fn random_seed() -> u64 {
+    SystemTime::now()
+        .duration_since(UNIX_EPOCH)
+        .map_or(0, |since| since.as_nanos() as u64)
+}
+
+/// Runs `termban generate`: makes the levels and writes them to `output`, or to a new
+/// file in the player's levels directory. Returns where they went.
+pub fn run(settings: &Settings, output: Option<&Path>) -> Result<PathBuf, String> {
+    let mut seed = settings.seed;
+    let mut levels = Vec::new();
+    for number in 1..=settings.count {
+        let mut level = generate(settings, &mut seed).ok_or_else(|| {
+            format!(
+                "Couldn't make a {}x{} level with {} boxes, try a bigger room or fewer boxes",
+                settings.width, settings.height, settings.boxes
+            )
+        })?;
+        level.world.name = number.to_string();
+        levels.push(level);
+    }
+
+    let path = output.map_or_else(
+        || {
+            let name = format!("generated-{}.ban", settings.seed);
+            storage::data_dir().join("levels").join(name)
+        },
+        Path::to_path_buf,
+    );
+    storage::write_atomic(&path, &to_ban(settings, &levels))
+        .map_err(|e| format!("Couldn't write {}: {}", path.display(), e))?;
+    Ok(path)
+}
+
+/// The levels as a level file, each with the best solution's length to aim for if the
+/// solver found it
+pub fn to_ban(settings: &Settings, levels: &[Generated]) -> String {
+    let mut text = format!(
+        "Title: Generated {}\nAuthor: termban generate --width {} --height {} --boxes {} --seed {}\n",
+        settings.seed, settings.width, settings.height, settings.boxes, settings.seed
+    );
+    for level in levels {
+        text.push_str(&format!("\n; {}\n", level.world.name));
+        if level.optimal {
+            text.push_str(&format!(
+                "Moves: {}\nPushes: {}\n",
+                level.solution.len(),
+                lurd::push_count(&level.solution)
+            ));
+        }
+        text.push_str(&format!("\n{}", to_xsb(&level.world)));
+    }
+    text
+}
+
+/// Makes one level, the best of a few tries, or `None` if the room's too cramped to fit
+/// the boxes in. Levels the solver could check beat any it couldn't.
+pub fn generate(settings: &Settings, seed: &mut u64) -> Option<Generated> {
+    (0..TRIES)
+        .filter_map(|_| attempt(settings, seed))
+        .max_by_key(|level| (level.optimal, level.score))
+}
+
+fn attempt(settings: &Settings, seed: &mut u64) -> Option<Generated> {
+    let board = room(settings.width, settings.height, seed);
+    let mut floor: Vec<Coordinate> = board
+        .indexed_iter()
+        .filter(|(_, tile)| **tile == Tile::Floor)
+        .map(|((y, x), _)| Coordinate { x, y })
+        .collect();
+    // Room for the boxes to move, not just to stand
+    if floor.len() < settings.boxes * 3 + 2 {
+        return None;
+    }
+
+    // Goals with their boxes on, and the player, all on different squares
+    shuffle(&mut floor, seed);
+    let mut board = board;
+    let mut entities = Vec::new();
+    for position in floor.iter().take(settings.boxes) {
+        board[position.arr_index()] = Tile::Goal;
+        entities.push(Entity::SokoBox(SokoBox {
+            position: position.clone(),
+        }));
+    }
+    entities.push(Entity::Player(Player {
+        position: floor[settings.boxes].clone(),
+    }));
+    let mut world = World {
+        name: String::new(),
+        board,
+        entities,
+        meta: Vec::new(),
+        camera_position: Coordinate { x: 0, y: 0 },
+    };
+
+    // The pulls wander back towards the goals as often as away, so a few positions
+    // along the way are tried and the best kept, rather than wherever they end
+    let mut best: Option<Generated> = None;
+    // The forward moves that undo every pull so far, last first
+    let mut undo = Vec::new();
+    // More pulls only make it harder, so once the solver gives up it isn't asked again
+    let mut solvable = true;
+    for step in 1..=PULLS {
+        // Pulls that would box the player in are left out, unless there's nothing else
+        let mut next: Vec<(Coordinate, Direction, World)> = pulls(&world)
+            .into_iter()
+            .map(|(position, direction)| {
+                let pulled = pull_from(&world, &position, direction);
+                (position, direction, pulled)
+            })
+            .collect();
+        if next.is_empty() {
+            break;
+        }
+        if next.iter().any(|(_, _, world)| !pulls(world).is_empty()) {
+            next.retain(|(_, _, world)| !pulls(world).is_empty());
+        }
+        let (position, direction, pulled) =
+            next.swap_remove((splitmix64(seed) % next.len() as u64) as usize);
+        let from = player_position(&world)?;
+        for walked in player_path(&world, &from, &position)? {
+            undo.push(lurd::to_char(walked.opposite(), false));
+        }
+        undo.push(lurd::to_char(direction.opposite(), true));
+        world = pulled;
+
+        if step % CHECKPOINT != 0 || world.is_sokoban_solved() {
+            continue;
+        }
+        let start = tidy(&world);
+        // Pushing back what was pulled always works, the solver finds the shortest way
+        // if the level isn't too big for it
+        let found = solvable
+            .then(|| solver::solve(&start, SOLVER_LIMIT))
+            .flatten();
+        solvable = found.is_some();
+        let optimal = found.is_some();
+        let Some(solution) =
+            found.or_else(|| shorten(&start, &undo.iter().rev().collect::<String>()))
+        else {
+            continue;
+        };
+        let score = solution.len() + 10 * box_switches(&start, &solution);
+        if best
+            .as_ref()
+            .is_none_or(|best| (optimal, score) > (best.optimal, best.score))
+        {
+            best = Some(Generated {
+                world: start,
+                solution,
+                optimal,
+                score,
+            });
+        }
+    }
+    best
+}
+
+/// Every pull the player could make next: where it would walk to first, and which way
+/// it would pull from there
+fn pulls(world: &World) -> Vec<(Coordinate, Direction)> {
+    let open = |position: &Coordinate| {
+        matches!(
+            world.board.get(position.arr_index()),
+            Some(Tile::Floor | Tile::Goal)
+        ) && !world.entities.iter().any(|entity| {
+            matches!(entity, Entity::SokoBox(_)) && entity.get_position() == *position
+        })
+    };
+    let Some(start) = player_position(world) else {
+        return Vec::new();
+    };
+
+    let mut reached = Array2::from_elem(world.board.dim(), false);
+    reached[start.arr_index()] = true;
+    let mut queue = VecDeque::from([start]);
+    let mut pulls = Vec::new();
+    while let Some(position) = queue.pop_front() {
+        for direction in Direction::ALL {
+            let next = get_new_position(position.clone(), &direction);
+            let behind = get_new_position(position.clone(), &direction.opposite());
+            let box_behind = world.entities.iter().any(|entity| {
+                matches!(entity, Entity::SokoBox(_)) && entity.get_position() == behind
+            });
+            if !open(&next) {
+                continue;
+            }
+            if box_behind {
+                pulls.push((position.clone(), direction));
+            }
+            if !reached[next.arr_index()] {
+                reached[next.arr_index()] = true;
+                queue.push_back(next);
+            }
+        }
+    }
+    pulls
+}
+
+/// `world` after the player walks to `position` and pulls in `direction` from there
+fn pull_from(world: &World, position: &Coordinate, direction: Direction) -> World {
+    let mut world = world.clone();
+    for entity in world.entities.iter_mut() {
+        if let Entity::Player(player) = entity {
+            player.position = position.clone();
+        }
+    }
+    apply_pull(&world, direction, false).0
+}
+
+/// A `width` by `height` room of wall with a connected patch of floor carved out of it
+/// by a random walk
+fn room(width: usize, height: usize, seed: &mut u64) -> Array2<Tile> {
+    let mut board = Array2::from_elem((height, width), Tile::Wall);
+    let inside = (width - 2) * (height - 2);
+    let target = (inside * OPEN / 100).max(1);
+
+    let mut position = Coordinate {
+        x: width / 2,
+        y: height / 2,
+    };
+    let mut carved = 0;
+    // Bounded, in case the walk keeps treading the same ground
+    for _ in 0..inside * 50 {
+        if board[position.arr_index()] == Tile::Wall {
+            board[position.arr_index()] = Tile::Floor;
+            carved += 1;
+            if carved == target {
+                break;
+            }
+        }
+        let direction = Direction::ALL[(splitmix64(seed) % 4) as usize];
+        let next = get_new_position(position.clone(), &direction);
+        // Keep the outer walls whole
+        if (1..width - 1).contains(&next.x) && (1..height - 1).contains(&next.y) {
+            position = next;
+        }
+    }
+    board
+}
+
+/// The level as it'll be saved: floor nobody can reach walled up, walls nobody would
+/// ever see left out, and the empty edges trimmed off
+fn tidy(world: &World) -> World {
+    let mut world = wall_in_unreachable(world);
+    let (height, width) = world.board.dim();
+    let near_floor = |board: &Array2<Tile>, y: usize, x: usize| {
+        (y.saturating_sub(1)..=(y + 1).min(height - 1)).any(|ny| {
+            (x.saturating_sub(1)..=(x + 1).min(width - 1))
+                .any(|nx| matches!(board[[ny, nx]], Tile::Floor | Tile::Goal))
+        })
+    };
+    let hidden: BTreeSet<(usize, usize)> = world
+        .board
+        .indexed_iter()
+        .filter(|((y, x), tile)| {
+            **tile == Tile::Wall && !near_floor(&world.board, *y, *x)
+        })
+        .map(|(index, _)| index)
+        .collect();
+    // Only from the edge inwards, a hole in the middle of the level would look odd
+    let mut queue: VecDeque<(usize, usize)> = hidden
+        .iter()
+        .filter(|(y, x)| *y == 0 || *x == 0 || *y == height - 1 || *x == width - 1)
+        .copied()
+        .collect();
+    let mut outside = BTreeSet::new();
+    while let Some((y, x)) = queue.pop_front() {
+        if !outside.insert((y, x)) {
+            continue;
+        }
+        let neighbours = [
+            (y.wrapping_sub(1), x),
+            (y + 1, x),
+            (y, x.wrapping_sub(1)),
+            (y, x + 1),
+        ];
+        queue.extend(
+            neighbours
+                .into_iter()
+                .filter(|index| hidden.contains(index) && !outside.contains(index)),
+        );
+    }
+    for index in outside {
+        world.board[index] = Tile::Empty;
+    }
+    world.normalized()
+}
+
+/// `solution` with every walk between pushes swapped for the shortest one, and the
+/// walk after the last push left off
+fn shorten(world: &World, solution: &str) -> Option<String> {
+    let mut shorter = String::new();
+    let mut world = world.clone();
+    // Where the walk to the next push set off from
+    let mut walk_start = world.clone();
+    for ch in solution.chars() {
+        let Some((direction, push)) = lurd::from_char(ch) else {
+            continue;
+        };
+        if push {
+            let from = player_position(&walk_start)?;
+            let to = player_position(&world)?;
+            for walked in player_path(&walk_start, &from, &to)? {
+                shorter.push(lurd::to_char(walked, false));
+            }
+            shorter.push(ch);
+        }
+        world = apply_move(&world, direction).0;
+        if push {
+            walk_start = world.clone();
+        }
+    }
+    Some(shorter)
+}
+
+/// How many times the solution stops pushing one box to push another
+fn box_switches(world: &World, solution: &str) -> usize {
+    let mut world = world.clone();
+    let mut last = None;
+    let mut switches = 0;
+    for ch in solution.chars() {
+        let Some((direction, _)) = lurd::from_char(ch) else {
+            continue;
+        };
+        let (next, outcome) = apply_move(&world, direction);
+        if let MoveOutcome::Pushed { box_index, .. }
+        | MoveOutcome::Solved { box_index, .. } = outcome
+        {
+            if last.is_some_and(|last| last != box_index) {
+                switches += 1;
+            }
+            last = Some(box_index);
+        }
+        world = next;
+    }
+    switches
+}
+
+/// Fisher-Yates
+fn shuffle<T>(items: &mut [T], seed: &mut u64) {
+    for i in (1..items.len()).rev() {
+        let j = (splitmix64(seed) % (i as u64 + 1)) as usize;
+        items.swap(i, j);
+    }
+}
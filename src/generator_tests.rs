@@ -0,0 +1,87 @@
+#[cfg(test)]
+mod tests {
+    use crate::collection;
+    use crate::generator::*;
+    use crate::lurd;
+    use crate::soko_game::apply_move;
+    use crate::soko_loader::to_xsb;
+    use crate::types::Entity;
+
+    fn settings(boxes: usize) -> Settings {
+        Settings {
+            width: 8,
+            height: 7,
+            boxes,
+            count: 3,
+            seed: 7,
+        }
+    }
+
+    #[test]
+    fn test_generated_level_is_solved_by_its_solution() {
+        let mut seed = 1;
+        let level = generate(&settings(2), &mut seed).unwrap();
+        let boxes = level
+            .world
+            .entities
+            .iter()
+            .filter(|entity| matches!(entity, Entity::SokoBox(_)))
+            .count();
+        assert_eq!(boxes, 2);
+        assert!(!level.world.is_sokoban_solved());
+
+        let world = level
+            .solution
+            .chars()
+            .fold(level.world.clone(), |world, ch| {
+                let (direction, _) = lurd::from_char(ch).unwrap();
+                apply_move(&world, direction).0
+            });
+        assert!(world.is_sokoban_solved());
+        assert!(level.score >= level.solution.len());
+    }
+
+    #[test]
+    fn test_same_seed_makes_same_level() {
+        let (mut first, mut second) = (42, 42);
+        let a = generate(&settings(2), &mut first).unwrap();
+        let b = generate(&settings(2), &mut second).unwrap();
+        assert_eq!(to_xsb(&a.world), to_xsb(&b.world));
+        assert_eq!(a.solution, b.solution);
+        assert_eq!(first, second);
+    }
+
+    #[test]
+    fn test_levels_load_back_from_the_file() {
+        let mut seed = 3;
+        let levels: Vec<Generated> = (1..=2)
+            .map(|number| {
+                let mut level = generate(&settings(1), &mut seed).unwrap();
+                level.world.name = number.to_string();
+                level
+            })
+            .collect();
+        let text = to_ban(&settings(1), &levels);
+        let (collection, worlds) =
+            collection::from_contents("generated-7", &text).unwrap();
+        assert_eq!(collection.title, "Generated 7");
+        assert_eq!(worlds.len(), 2);
+        for (world, level) in worlds.iter().zip(&levels) {
+            assert_eq!(world.name, level.world.name);
+            assert_eq!(to_xsb(world), to_xsb(&level.world));
+        }
+    }
+
+    #[test]
+    fn test_too_many_boxes_for_the_room() {
+        let cramped = Settings {
+            width: 5,
+            height: 5,
+            boxes: 6,
+            count: 1,
+            seed: 1,
+        };
+        let mut seed = cramped.seed;
+        assert!(generate(&cramped, &mut seed).is_none());
+    }
+}
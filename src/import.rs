@@ -0,0 +1,184 @@
+/*!
+ * Description:
+ *
+ * Imports solutions published alongside a level collection, so they can be watched in
+ * the replay viewer. Each solution is matched to a level by its title, replayed through
+ * the move rules, and only kept in the save file if it really solves the level.
+ *
+ * Usage:
+ *
+ *   termban import levels.ban solutions.txt
+ *
+ * Notes:
+ *
+ * Solution files aren't standardised, so this reads them loosely. A line of LURD moves,
+ * or run length encoded LURD like `3r2U`, is a solution. Lines of moves one after
+ * another make one solution. Any other line names the level the solutions after it are
+ * for: `; 3`, `Title: 3`, or just `3`. `Solution:` lines, other `Key: value` lines and
+ * the boards themselves are skipped over.
+ *
+ * A title that doesn't match any level but is a number, like `12` or `Level 12`, picks
+ * the level by its position in the file instead.
+ *
+ * Imported solutions go in the active profile. They don't mark a level as solved, that's
+ * still up to the player, and a solution already saved is only replaced by a shorter one.
+ */
+
+use std::path::Path;
+
+use crate::storage::read_file;
+use crate::types::{Collection, RunningState, SaveFile, World};
+use crate::{collection, lurd, replay};
+
+/// Runs `termban import`: imports the solutions in `solutions` for the levels in
+/// `levels` into `save_file`, and returns a line about each solution found
+pub fn run(
+    levels: &Path,
+    solutions: &Path,
+    save_file: &mut SaveFile,
+) -> Result<Vec<String>, String> {
+    let (collection, worlds) = collection::load(levels)?;
+    let text = read_file(solutions)
+        .map_err(|e| format!("Couldn't read {}: {}", solutions.display(), e))?;
+    let found = parse_solutions(&text);
+    if found.is_empty() {
+        return Err(format!("No solutions found in {}", solutions.display()));
+    }
+    // Progress saved under the file's earlier layout follows its levels
+    save_file.adopt_collection(&collection);
+    Ok(import(save_file, &collection, &worlds, &found))
+}
+
+/// The solutions in a solution file, each with the title of the level it's for
+pub fn parse_solutions(text: &str) -> Vec<(String, String)> {
+    let mut found = Vec::new();
+    let mut title = String::new();
+    let mut solution = String::new();
+    for line in text.lines().map(str::trim) {
+        let moves = match line.split_once(':') {
+            Some((key, moves)) if key.trim().to_lowercase().starts_with("solution") => {
+                moves.trim()
+            }
+            _ => line,
+        };
+        if !moves.is_empty() && is_moves(moves) {
+            solution.push_str(moves);
+            continue;
+        }
+        if !solution.is_empty() {
+            found.push((title.clone(), std::mem::take(&mut solution)));
+        }
+        // A one word key makes a `Key: value` line, `Level 3: The Hall` is a title
+        let field = line
+            .split_once(':')
+            .filter(|(key, _)| !key.trim().contains(' '));
+        if let Some(comment) = line.strip_prefix(';') {
+            title = comment.trim().to_string();
+        } else if line.is_empty()
+            || is_board(line)
+            || line.to_lowercase().starts_with("solution")
+        {
+            continue;
+        } else if let Some((key, value)) = field {
+            if key.trim().eq_ignore_ascii_case("title") {
+                title = value.trim().to_string();
+            }
+        } else {
+            title = line.to_string();
+        }
+    }
+    if !solution.is_empty() {
+        found.push((title, solution));
+    }
+    found
+}
+
+/// Moves, possibly run length encoded, with at least one actual move in them
+fn is_moves(line: &str) -> bool {
+    line.chars().any(|ch| lurd::from_char(ch).is_some())
+        && line.chars().all(|ch| {
+            lurd::from_char(ch).is_some()
+                || ch.is_ascii_digit()
+                || ch.is_whitespace()
+                || ch == '('
+                || ch == ')'
+        })
+}
+
+/// A row of a level, the boards are often printed along with their solutions
+fn is_board(line: &str) -> bool {
+    line.contains('#') && line.chars().all(|ch| " #@+$*.-_".contains(ch))
+}
+
+/// Checks `solution` solves `world`, and returns its moves as plain LURD with the pushes
+/// in upper case
+pub fn verify(world: &World, solution: &str) -> Result<String, String> {
+    let moves = lurd::expand(solution)?;
+    let replay = replay::load(world, &moves, RunningState::Menu)?;
+    if !replay
+        .worlds
+        .last()
+        .is_some_and(|world| world.is_sokoban_solved())
+    {
+        return Err("It doesn't solve the level".to_string());
+    }
+    Ok(replay.moves)
+}
+
+/// The level a solution file's `title` is for
+fn find_level(worlds: &[World], title: &str) -> Option<usize> {
+    if let Some(index) = worlds
+        .iter()
+        .position(|world| world.name.trim().eq_ignore_ascii_case(title))
+    {
+        return Some(index);
+    }
+    let lower = title.to_lowercase();
+    let number = lower.strip_prefix("level").unwrap_or(&lower).trim();
+    number
+        .parse::<usize>()
+        .ok()
+        .filter(|number| (1..=worlds.len()).contains(number))
+        .map(|number| number - 1)
+}
+
+/// Stores each solution that solves its level, and says what happened to each one
+pub fn import(
+    save_file: &mut SaveFile,
+    collection: &Collection,
+    worlds: &[World],
+    solutions: &[(String, String)],
+) -> Vec<String> {
+    let save = save_file.current_mut();
+    solutions
+        .iter()
+        .map(|(title, solution)| {
+            let Some(index) = find_level(worlds, title) else {
+                if title.is_empty() {
+                    return "A solution with no title before it: skipped".to_string();
+                }
+                return format!("{}: no level has that title", title);
+            };
+            let name = format!("Level {} ({})", index + 1, worlds[index].name);
+            let moves = match verify(&worlds[index], solution) {
+                Ok(moves) => moves,
+                Err(e) => return format!("{}: not imported, {}", name, e),
+            };
+            let level = save.level_mut(collection, index);
+            match &level.solution {
+                Some(best) if best.len() <= moves.len() => {
+                    format!(
+                        "{}: kept the {} move solution already saved",
+                        name,
+                        best.len()
+                    )
+                }
+                _ => {
+                    let report = format!("{}: imported, {} moves", name, moves.len());
+                    level.solution = Some(moves);
+                    report
+                }
+            }
+        })
+        .collect()
+}
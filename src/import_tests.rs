@@ -0,0 +1,113 @@
+#[cfg(test)]
+mod tests {
+    use crate::collection::from_contents;
+    use crate::import::*;
+    use crate::lurd::{expand, MAX_MOVES};
+    use crate::types::SaveFile;
+
+    const LEVELS: &str = "; Corridor
+#######
+#@ $ .#
+#######
+
+; Step
+####
+#@ #
+#$ #
+#. #
+####
+";
+
+    #[test]
+    fn test_expand_run_length_moves() {
+        assert_eq!(expand("3r2U").unwrap(), "rrrUU");
+        assert_eq!(expand("2(lU)d").unwrap(), "lUlUd");
+        assert_eq!(expand("2(r2(Ul))").unwrap(), "rUlUlrUlUl");
+        assert_eq!(expand("rr\nUU").unwrap(), "rrUU");
+        assert_eq!(expand("12l").unwrap(), "l".repeat(12));
+    }
+
+    #[test]
+    fn test_expand_rejects_what_isnt_moves() {
+        assert!(expand("3x").is_err());
+        assert!(expand("2(lU").is_err());
+        assert!(expand("lU)").is_err());
+        assert!(expand("r3").is_err());
+        assert!(expand("0r").is_err());
+        assert!(expand("0(lU)").is_err());
+        assert!(expand("r10(00U)").is_err());
+        // Counts too big to hold, or to spell out, are refused rather than tried
+        assert!(expand("4000000000000000000000r").is_err());
+        assert!(expand("99999999999r").is_err());
+        assert!(expand("1000(1000(rl))").is_err());
+        assert_eq!(expand("1000(1000r)").unwrap().len(), MAX_MOVES);
+    }
+
+    #[test]
+    fn test_parse_solutions_follows_the_titles() {
+        let text = "Title: Microban solutions
+
+; Corridor
+Solution (3 moves):
+rRR
+
+Level 2
+####
+#@ #
+Solution: D
+2
+3r
+2U
+";
+        assert_eq!(
+            parse_solutions(text),
+            vec![
+                ("Corridor".to_string(), "rRR".to_string()),
+                ("Level 2".to_string(), "D".to_string()),
+                ("2".to_string(), "3r2U".to_string()),
+            ]
+        );
+    }
+
+    #[test]
+    fn test_verify_fixes_up_pushes() {
+        let (_, worlds) = from_contents("test", LEVELS).unwrap();
+        assert_eq!(verify(&worlds[0], "2rr").unwrap(), "rRR");
+        assert_eq!(
+            verify(&worlds[0], "rr").unwrap_err(),
+            "It doesn't solve the level"
+        );
+        assert!(verify(&worlds[0], "l").is_err());
+    }
+
+    #[test]
+    fn test_import_keeps_the_shortest_solution() {
+        let (collection, worlds) = from_contents("test", LEVELS).unwrap();
+        let mut save_file = SaveFile::new();
+        let solutions = vec![
+            ("corridor".to_string(), "rlrRR".to_string()),
+            ("Corridor".to_string(), "rRR".to_string()),
+            ("Corridor".to_string(), "rlrRR".to_string()),
+            ("2".to_string(), "u".to_string()),
+            ("Hall".to_string(), "r".to_string()),
+        ];
+
+        let report = import(&mut save_file, &collection, &worlds, &solutions);
+        assert_eq!(
+            report,
+            vec![
+                "Level 1 (Corridor): imported, 5 moves",
+                "Level 1 (Corridor): imported, 3 moves",
+                "Level 1 (Corridor): kept the 3 move solution already saved",
+                "Level 2 (Step): not imported, Move 1 (Up) is blocked",
+                "Hall: no level has that title",
+            ]
+        );
+
+        let level = save_file.current().level(&collection, 0).unwrap();
+        assert_eq!(level.solution.as_deref(), Some("rRR"));
+        // Watching someone else's solution isn't solving it
+        assert!(!level.solved);
+        assert!(save_file.current().level(&collection, 1).is_none());
+    }
+}
@@ -0,0 +1,504 @@
+/*!
+ * Description:
+ *
+ * Key bindings for every screen, and the `keys.toml` file that changes them.
+ *
+ * Each screen looks up the action for a key press here rather than matching key codes
+ * itself, and the help text is written from the same table so it always shows the keys
+ * that actually work. Letters match whichever case is typed, so caps lock can't get in
+ * the way.
+ *
+ * Usage:
+ *
+ *   let (keymap, problems) = keymap::load();
+ *   match keymap.action(Screen::Game, &key) { Some(Action::Undo) => ..., _ => ... }
+ *
+ * Notes:
+ *
+ * `keys.toml` lives in the data directory next to the saves. It picks a preset and can
+ * then rebind any action, a list of keys replacing that action's keys from the preset:
+ *
+ *   preset = "vi"          # "default", or "vi" to add hjkl movement
+ *
+ *   [game]
+ *   undo = ["z", "u", "Backspace"]
+ *
+ * Keys are single characters or one of the names in `parse_key`.
+ */
+
+use std::io;
+use std::path::PathBuf;
+
+use ratatui::crossterm::event::{KeyCode, KeyEvent};
+use toml::{Table, Value};
+
+use crate::storage;
+
+pub const KEYMAP_FILENAME: &str = "keys.toml";
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Screen {
+    Menu,
+    Collections,
+    Levels,
+    Game,
+    Replay,
+    Profiles,
+    Victory,
+    Summary,
+}
+
+impl Screen {
+    pub const ALL: [Screen; 8] = [
+        Screen::Menu,
+        Screen::Collections,
+        Screen::Levels,
+        Screen::Game,
+        Screen::Replay,
+        Screen::Profiles,
+        Screen::Victory,
+        Screen::Summary,
+    ];
+
+    /// The table the screen's bindings go under in `keys.toml`
+    pub fn name(self) -> &'static str {
+        match self {
+            Screen::Menu => "menu",
+            Screen::Collections => "collections",
+            Screen::Levels => "levels",
+            Screen::Game => "game",
+            Screen::Replay => "replay",
+            Screen::Profiles => "profiles",
+            Screen::Victory => "victory",
+            Screen::Summary => "summary",
+        }
+    }
+}
+
+/// Something a key can be bound to. Which screens offer which actions is up to
+/// `BINDINGS`.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Action {
+    Start,
+    Profiles,
+    Erase,
+    Quit,
+    Up,
+    Down,
+    Left,
+    Right,
+    First,
+    Last,
+    Open,
+    Back,
+    PageUp,
+    PageDown,
+    Play,
+    StartFresh,
+    PlayMirrored,
+    Replay,
+    Search,
+    Skip,
+    Unlock,
+    Undo,
+    Reset,
+    Reverse,
+    Animation,
+    Help,
+    CopyLevel,
+    CopyMoves,
+    ZoomFar,
+    ZoomMiddle,
+    ZoomClose,
+    Forward,
+    Faster,
+    Slower,
+    Switch,
+    New,
+    Rename,
+    Delete,
+    Next,
+    Retry,
+    Collections,
+    Levels,
+}
+
+impl Action {
+    /// The action's name in `keys.toml`
+    pub fn name(self) -> &'static str {
+        match self {
+            Action::Start => "start",
+            Action::Profiles => "profiles",
+            Action::Erase => "erase",
+            Action::Quit => "quit",
+            Action::Up => "up",
+            Action::Down => "down",
+            Action::Left => "left",
+            Action::Right => "right",
+            Action::First => "first",
+            Action::Last => "last",
+            Action::Open => "open",
+            Action::Back => "back",
+            Action::PageUp => "page_up",
+            Action::PageDown => "page_down",
+            Action::Play => "play",
+            Action::StartFresh => "start_fresh",
+            Action::PlayMirrored => "play_mirrored",
+            Action::Replay => "replay",
+            Action::Search => "search",
+            Action::Skip => "skip",
+            Action::Unlock => "unlock",
+            Action::Undo => "undo",
+            Action::Reset => "reset",
+            Action::Reverse => "reverse",
+            Action::Animation => "animation",
+            Action::Help => "help",
+            Action::CopyLevel => "copy_level",
+            Action::CopyMoves => "copy_moves",
+            Action::ZoomFar => "zoom_far",
+            Action::ZoomMiddle => "zoom_middle",
+            Action::ZoomClose => "zoom_close",
+            Action::Forward => "forward",
+            Action::Faster => "faster",
+            Action::Slower => "slower",
+            Action::Switch => "switch",
+            Action::New => "new",
+            Action::Rename => "rename",
+            Action::Delete => "delete",
+            Action::Next => "next",
+            Action::Retry => "retry",
+            Action::Collections => "collections",
+            Action::Levels => "levels",
+        }
+    }
+}
+
+/// One action on one screen, with the keys each preset gives it
+pub struct Binding {
+    pub screen: Screen,
+    pub action: Action,
+    description: &'static str,
+    keys: &'static [&'static str],
+    /// The keys in the vi preset, when they differ from the default ones
+    vi: Option<&'static [&'static str]>,
+}
+
+const fn bind(
+    screen: Screen,
+    action: Action,
+    description: &'static str,
+    keys: &'static [&'static str],
+) -> Binding {
+    Binding {
+        screen,
+        action,
+        description,
+        keys,
+        vi: None,
+    }
+}
+
+const fn bind_vi(
+    screen: Screen,
+    action: Action,
+    description: &'static str,
+    keys: &'static [&'static str],
+    vi: &'static [&'static str],
+) -> Binding {
+    Binding {
+        screen,
+        action,
+        description,
+        keys,
+        vi: Some(vi),
+    }
+}
+
+#[rustfmt::skip]
+pub const BINDINGS: &[Binding] = &[
+    bind(Screen::Menu, Action::Start, "Begin", &["Enter", "Space"]),
+    bind(Screen::Menu, Action::Profiles, "Change Profile", &["p"]),
+    bind(Screen::Menu, Action::Erase, "Erase Save Data", &["Delete"]),
+    bind(Screen::Menu, Action::Quit, "Leave", &["Esc"]),
+
+    bind_vi(Screen::Collections, Action::Up, "Up", &["Up", "w"], &["Up", "w", "k"]),
+    bind_vi(Screen::Collections, Action::Down, "Down", &["Down", "s"], &["Down", "s", "j"]),
+    bind(Screen::Collections, Action::First, "First", &["Home"]),
+    bind(Screen::Collections, Action::Last, "Last", &["End"]),
+    bind(Screen::Collections, Action::Open, "Open", &["Enter", "Space"]),
+    bind(Screen::Collections, Action::Back, "Back", &["Esc"]),
+
+    bind_vi(Screen::Levels, Action::Up, "Up", &["Up", "w"], &["Up", "w", "k"]),
+    bind_vi(Screen::Levels, Action::Down, "Down", &["Down", "s"], &["Down", "s", "j"]),
+    bind(Screen::Levels, Action::PageUp, "Page Up", &["PageUp"]),
+    bind(Screen::Levels, Action::PageDown, "Page Down", &["PageDown"]),
+    bind(Screen::Levels, Action::First, "First", &["Home"]),
+    bind(Screen::Levels, Action::Last, "Last", &["End"]),
+    bind(Screen::Levels, Action::Play, "Play", &["Enter", "Space"]),
+    bind(Screen::Levels, Action::StartFresh, "Start Fresh", &["n"]),
+    bind(Screen::Levels, Action::PlayMirrored, "Play Mirrored", &["m"]),
+    bind(Screen::Levels, Action::Replay, "Replay", &["v"]),
+    bind(Screen::Levels, Action::Search, "Search", &["/"]),
+    bind_vi(Screen::Levels, Action::Skip, "Skip", &["k"], &["x"]),
+    bind(Screen::Levels, Action::Unlock, "Unlock", &["u"]),
+    bind(Screen::Levels, Action::Back, "Back", &["Esc"]),
+
+    bind_vi(Screen::Game, Action::Up, "Move Up", &["Up", "w"], &["Up", "w", "k"]),
+    bind_vi(Screen::Game, Action::Down, "Move Down", &["Down", "s"], &["Down", "s", "j"]),
+    bind_vi(Screen::Game, Action::Left, "Move Left", &["Left", "a"], &["Left", "a", "h"]),
+    bind_vi(Screen::Game, Action::Right, "Move Right", &["Right", "d"], &["Right", "d", "l"]),
+    bind(Screen::Game, Action::Undo, "Undo a Move", &["z", "u"]),
+    bind(Screen::Game, Action::Reset, "Restart Level", &["r"]),
+    bind(Screen::Game, Action::Reverse, "Play the Level in Reverse", &["b"]),
+    bind(Screen::Game, Action::Animation, "Turn Animation On or Off", &["m"]),
+    bind(Screen::Game, Action::Help, "Help / Pause", &["?"]),
+    bind(Screen::Game, Action::CopyLevel, "Copy the Level", &["c"]),
+    bind(Screen::Game, Action::CopyMoves, "Copy the Moves So Far", &["y"]),
+    bind(Screen::Game, Action::ZoomFar, "Zoom Out", &["1"]),
+    bind(Screen::Game, Action::ZoomMiddle, "Zoom Middle", &["2"]),
+    bind(Screen::Game, Action::ZoomClose, "Zoom In", &["3"]),
+    bind(Screen::Game, Action::Quit, "Back to Level Select", &["Esc"]),
+
+    bind(Screen::Replay, Action::Play, "Play / Pause", &["Space", "Enter"]),
+    bind_vi(Screen::Replay, Action::Forward, "Step Forward", &["Right", "d"], &["Right", "d", "l"]),
+    bind_vi(Screen::Replay, Action::Back, "Step Back", &["Left", "a"], &["Left", "a", "h"]),
+    bind_vi(Screen::Replay, Action::Faster, "Faster", &["Up", "w", "+"], &["Up", "w", "+", "k"]),
+    bind_vi(Screen::Replay, Action::Slower, "Slower", &["Down", "s", "-"], &["Down", "s", "-", "j"]),
+    bind(Screen::Replay, Action::First, "Back to the Start", &["Home"]),
+    bind(Screen::Replay, Action::Last, "Skip to the End", &["End"]),
+    bind(Screen::Replay, Action::ZoomFar, "Zoom Out", &["1"]),
+    bind(Screen::Replay, Action::ZoomMiddle, "Zoom Middle", &["2"]),
+    bind(Screen::Replay, Action::ZoomClose, "Zoom In", &["3"]),
+    bind(Screen::Replay, Action::Quit, "Leave", &["Esc"]),
+
+    bind_vi(Screen::Profiles, Action::Up, "Up", &["Up", "w"], &["Up", "w", "k"]),
+    bind_vi(Screen::Profiles, Action::Down, "Down", &["Down", "s"], &["Down", "s", "j"]),
+    bind(Screen::Profiles, Action::Switch, "Play as", &["Enter", "Space"]),
+    bind(Screen::Profiles, Action::New, "New", &["n"]),
+    bind(Screen::Profiles, Action::Rename, "Rename", &["r"]),
+    bind(Screen::Profiles, Action::Delete, "Delete", &["d", "Delete"]),
+    bind(Screen::Profiles, Action::Back, "Back", &["Esc"]),
+
+    bind(Screen::Victory, Action::Next, "Next Level", &["Enter", "Space", "n"]),
+    bind(Screen::Victory, Action::Replay, "Watch the Solution", &["v"]),
+    bind(Screen::Victory, Action::Retry, "Play It Again", &["r"]),
+    bind(Screen::Victory, Action::Back, "Back to Level Select", &["Esc"]),
+
+    bind(Screen::Summary, Action::Collections, "Choose Another Collection", &["Enter", "Space", "Esc"]),
+    bind(Screen::Summary, Action::Levels, "Back to Level Select", &["l"]),
+];
+
+#[derive(Debug, Clone)]
+pub struct Keymap {
+    /// The keys for each entry in `BINDINGS`, in the same order
+    keys: Vec<Vec<KeyCode>>,
+}
+
+impl Default for Keymap {
+    fn default() -> Self {
+        Keymap::preset("default").expect("the default preset exists")
+    }
+}
+
+impl Keymap {
+    pub fn preset(name: &str) -> Option<Keymap> {
+        let vi = match name {
+            "default" => false,
+            "vi" => true,
+            _ => return None,
+        };
+        let keys = BINDINGS
+            .iter()
+            .map(|binding| {
+                let keys = binding.vi.filter(|_| vi).unwrap_or(binding.keys);
+                keys.iter().filter_map(|key| parse_key(key)).collect()
+            })
+            .collect();
+        Some(Keymap { keys })
+    }
+
+    /// Builds a keymap from the contents of a `keys.toml`. Anything that doesn't make
+    /// sense is passed over and described in the second half of the result.
+    pub fn from_toml(contents: &str) -> (Keymap, Vec<String>) {
+        let table: Table = match contents.parse() {
+            Ok(table) => table,
+            Err(e) => return (Keymap::default(), vec![e.message().to_string()]),
+        };
+        let mut problems = Vec::new();
+
+        let mut keymap = match table.get("preset") {
+            None => Keymap::default(),
+            Some(Value::String(name)) => Keymap::preset(name).unwrap_or_else(|| {
+                problems.push(format!("there is no '{}' preset", name));
+                Keymap::default()
+            }),
+            Some(_) => {
+                problems.push("the preset should be a name".to_string());
+                Keymap::default()
+            }
+        };
+
+        for (name, actions) in table.iter().filter(|(name, _)| *name != "preset") {
+            let Some(screen) = Screen::ALL.into_iter().find(|s| s.name() == name)
+            else {
+                problems.push(format!("there is no '{}' screen", name));
+                continue;
+            };
+            let Value::Table(actions) = actions else {
+                problems.push(format!("[{}] should be a table of actions", name));
+                continue;
+            };
+            for (action, keys) in actions {
+                let Some(index) = BINDINGS
+                    .iter()
+                    .position(|b| b.screen == screen && b.action.name() == action)
+                else {
+                    problems
+                        .push(format!("there is no '{}' action in [{}]", action, name));
+                    continue;
+                };
+                match parse_keys(keys) {
+                    Ok(keys) => keymap.keys[index] = keys,
+                    Err(e) => problems.push(format!("{}.{}: {}", name, action, e)),
+                }
+            }
+        }
+        (keymap, problems)
+    }
+
+    /// The action a key press is bound to on `screen`
+    pub fn action(&self, screen: Screen, key: &KeyEvent) -> Option<Action> {
+        BINDINGS
+            .iter()
+            .zip(self.keys.iter())
+            .filter(|(binding, _)| binding.screen == screen)
+            .find(|(_, keys)| keys.iter().any(|bound| matches_key(bound, &key.code)))
+            .map(|(binding, _)| binding.action)
+    }
+
+    /// The keys bound to an action, for showing to the player, like "Up / W"
+    pub fn keys(&self, screen: Screen, action: Action) -> String {
+        BINDINGS
+            .iter()
+            .zip(self.keys.iter())
+            .find(|(binding, _)| binding.screen == screen && binding.action == action)
+            .map(|(_, keys)| keys.iter().map(key_label).collect::<Vec<_>>().join(" / "))
+            .unwrap_or_default()
+    }
+
+    /// Just the first key bound to an action, for short hints
+    pub fn key(&self, screen: Screen, action: Action) -> String {
+        let keys = self.keys(screen, action);
+        keys.split(" / ").next().unwrap_or_default().to_string()
+    }
+
+    /// A line for each action on `screen` listing its keys and what it does
+    pub fn help(&self, screen: Screen) -> String {
+        BINDINGS
+            .iter()
+            .filter(|binding| binding.screen == screen)
+            .map(|binding| {
+                let keys = self.keys(screen, binding.action);
+                format!("    {:<20}{}\n", keys, binding.description)
+            })
+            .collect()
+    }
+}
+
+pub fn keymap_path() -> PathBuf {
+    storage::data_dir().join(KEYMAP_FILENAME)
+}
+
+/// Loads `keys.toml` from the data directory, or the default keys if there isn't one.
+/// Returns a note for the player if the file had problems.
+pub fn load() -> (Keymap, Option<String>) {
+    let path = keymap_path();
+    let contents = match storage::read_file(&path) {
+        Ok(contents) => contents,
+        Err(e) if e.kind() == io::ErrorKind::NotFound => {
+            return (Keymap::default(), None)
+        }
+        Err(e) => {
+            let notice = format!("Couldn't read {}: {}", path.display(), e);
+            return (Keymap::default(), Some(notice));
+        }
+    };
+    let (keymap, problems) = Keymap::from_toml(&contents);
+    let notice = (!problems.is_empty()).then(|| {
+        format!(
+            "Some key bindings in {} were ignored: {}.",
+            path.display(),
+            problems.join("; ")
+        )
+    });
+    (keymap, notice)
+}
+
+fn parse_keys(keys: &Value) -> Result<Vec<KeyCode>, String> {
+    let Value::Array(keys) = keys else {
+        return Err("keys should be a list, like [\"w\", \"Up\"]".to_string());
+    };
+    keys.iter()
+        .map(|key| match key {
+            Value::String(key) => {
+                parse_key(key).ok_or_else(|| format!("'{}' is not a key", key))
+            }
+            _ => Err("keys should be written as strings".to_string()),
+        })
+        .collect()
+}
+
+/// A key from its name in `keys.toml`: a single character, or one of the names below
+pub fn parse_key(name: &str) -> Option<KeyCode> {
+    let mut chars = name.chars();
+    if let (Some(ch), None) = (chars.next(), chars.next()) {
+        return Some(KeyCode::Char(ch));
+    }
+    let code = match name.to_ascii_lowercase().as_str() {
+        "up" => KeyCode::Up,
+        "down" => KeyCode::Down,
+        "left" => KeyCode::Left,
+        "right" => KeyCode::Right,
+        "enter" => KeyCode::Enter,
+        "esc" | "escape" => KeyCode::Esc,
+        "space" => KeyCode::Char(' '),
+        "backspace" => KeyCode::Backspace,
+        "tab" => KeyCode::Tab,
+        "home" => KeyCode::Home,
+        "end" => KeyCode::End,
+        "pageup" => KeyCode::PageUp,
+        "pagedown" => KeyCode::PageDown,
+        "delete" => KeyCode::Delete,
+        "insert" => KeyCode::Insert,
+        function => {
+            let number = function.strip_prefix('f')?.parse().ok()?;
+            KeyCode::F(number)
+        }
+    };
+    Some(code)
+}
+
+fn key_label(code: &KeyCode) -> String {
+    match code {
+        KeyCode::Char(' ') => "Space".to_string(),
+        KeyCode::Char(ch) => ch.to_ascii_uppercase().to_string(),
+        KeyCode::Up => "↑".to_string(),
+        KeyCode::Down => "↓".to_string(),
+        KeyCode::Left => "←".to_string(),
+        KeyCode::Right => "→".to_string(),
+        KeyCode::PageUp => "PgUp".to_string(),
+        KeyCode::PageDown => "PgDn".to_string(),
+        KeyCode::F(number) => format!("F{}", number),
+        other => format!("{:?}", other),
+    }
+}
+
+/// Letters match in either case
+fn matches_key(bound: &KeyCode, pressed: &KeyCode) -> bool {
+    match (bound, pressed) {
+        (KeyCode::Char(bound), KeyCode::Char(pressed)) => {
+            bound.eq_ignore_ascii_case(pressed)
+        }
+        _ => bound == pressed,
+    }
+}
@@ -0,0 +1,122 @@
+#[cfg(test)]
+mod tests {
+    use crate::keymap::*;
+    use ratatui::crossterm::event::{KeyCode, KeyEvent, KeyModifiers};
+
+    fn press(code: KeyCode) -> KeyEvent {
+        KeyEvent::new(code, KeyModifiers::NONE)
+    }
+
+    #[test]
+    fn test_default_keys() {
+        let keymap = Keymap::default();
+        let action = |code| keymap.action(Screen::Game, &press(code));
+
+        assert_eq!(action(KeyCode::Up), Some(Action::Up));
+        assert_eq!(action(KeyCode::Char('w')), Some(Action::Up));
+        // Caps lock doesn't stop anything working
+        assert_eq!(action(KeyCode::Char('W')), Some(Action::Up));
+        assert_eq!(action(KeyCode::Char('k')), None);
+        assert_eq!(action(KeyCode::Esc), Some(Action::Quit));
+        assert_eq!(action(KeyCode::Char('?')), Some(Action::Help));
+        // Keys belong to a screen
+        assert_eq!(
+            keymap.action(Screen::Levels, &press(KeyCode::Char('k'))),
+            Some(Action::Skip)
+        );
+    }
+
+    #[test]
+    fn test_vi_preset_adds_hjkl() {
+        let keymap = Keymap::preset("vi").unwrap();
+
+        let game = |ch| keymap.action(Screen::Game, &press(KeyCode::Char(ch)));
+        assert_eq!(game('h'), Some(Action::Left));
+        assert_eq!(game('j'), Some(Action::Down));
+        assert_eq!(game('k'), Some(Action::Up));
+        assert_eq!(game('l'), Some(Action::Right));
+        assert_eq!(game('w'), Some(Action::Up));
+
+        // Skip moves out of the way of moving up the level list
+        let levels = |ch| keymap.action(Screen::Levels, &press(KeyCode::Char(ch)));
+        assert_eq!(levels('k'), Some(Action::Up));
+        assert_eq!(levels('x'), Some(Action::Skip));
+    }
+
+    #[test]
+    fn test_keys_file_rebinds_actions() {
+        let (keymap, problems) = Keymap::from_toml(
+            r#"
+            preset = "vi"
+
+            [game]
+            undo = ["Backspace", "F2"]
+            "#,
+        );
+        assert!(problems.is_empty(), "{:?}", problems);
+
+        let game = |code| keymap.action(Screen::Game, &press(code));
+        assert_eq!(game(KeyCode::Backspace), Some(Action::Undo));
+        assert_eq!(game(KeyCode::F(2)), Some(Action::Undo));
+        assert_eq!(game(KeyCode::Char('z')), None);
+        assert_eq!(game(KeyCode::Char('h')), Some(Action::Left));
+        assert_eq!(keymap.keys(Screen::Game, Action::Undo), "Backspace / F2");
+    }
+
+    #[test]
+    fn test_keys_file_problems_are_reported() {
+        let (keymap, problems) = Keymap::from_toml(
+            r#"
+            preset = "emacs"
+
+            [game]
+            undo = ["NotAKey"]
+            fly = ["f"]
+
+            [kitchen]
+            "#,
+        );
+        assert_eq!(problems.len(), 4, "{:?}", problems);
+
+        // Everything that did make sense still works
+        let game = |code| keymap.action(Screen::Game, &press(code));
+        assert_eq!(game(KeyCode::Char('z')), Some(Action::Undo));
+    }
+
+    #[test]
+    fn test_help_lists_the_bound_keys() {
+        let help = Keymap::default().help(Screen::Menu);
+        assert!(help.contains("Enter / Space"));
+        assert!(help.contains("Begin"));
+        assert_eq!(Keymap::default().key(Screen::Levels, Action::Replay), "V");
+    }
+
+    #[test]
+    fn test_every_action_can_be_rebound_by_name() {
+        for screen in Screen::ALL {
+            let bound: Vec<Action> = BINDINGS
+                .iter()
+                .filter(|binding| binding.screen == screen)
+                .map(|binding| binding.action)
+                .collect();
+            for action in &bound {
+                let (keymap, problems) = Keymap::from_toml(&format!(
+                    "[{}]\n{} = [\"F12\"]",
+                    screen.name(),
+                    action.name()
+                ));
+                assert!(problems.is_empty(), "{:?}", problems);
+                assert_eq!(
+                    keymap.action(screen, &press(KeyCode::F(12))),
+                    Some(*action)
+                );
+            }
+            // No two actions on a screen share a name
+            let mut names: Vec<&str> =
+                bound.iter().map(|action| action.name()).collect();
+            names.sort();
+            names.dedup();
+            assert_eq!(names.len(), bound.len(), "{}", screen.name());
+        }
+    }
+}
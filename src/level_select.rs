@@ -1,102 +1,358 @@
 use std::io;
 use std::time::Duration;
 
+use crate::keymap::{Action, Screen};
+use crate::transform::Symmetry;
 use crate::{
-    copy_text,
-    types::{LevelSelectAction, Model, RunningState},
+    copy_text, replay, soko_game,
+    types::{GameWindow, LevelSelectAction, Model, RunningState, Unlock, Zoom},
 };
+use ratatui::prelude::*;
 use ratatui::{
     crossterm::event::{self, Event, KeyCode},
-    widgets::Paragraph,
+    widgets::{Block, List, ListItem, Paragraph},
     Frame,
 };
 
 pub fn view(model: &mut Model, frame: &mut Frame) {
-    let mut view_text = copy_text::LEVEL_SELECT.to_string();
+    let header_height = copy_text::LEVEL_SELECT.lines().count() as u16;
+    let [header_area, body_area, prompt_area] = Layout::vertical([
+        Constraint::Length(header_height),
+        Constraint::Min(0),
+        Constraint::Length(1),
+    ])
+    .areas(frame.area());
+    let [list_area, preview_area] =
+        Layout::horizontal([Constraint::Percentage(40), Constraint::Percentage(60)])
+            .areas(body_area);
 
-    let selected_world_index = model.game.world_index;
-    // Get all of the names as a vector of strings
-    let world_names: Vec<String> = model
+    frame.render_widget(Paragraph::new(copy_text::LEVEL_SELECT), header_area);
+
+    let items: Vec<ListItem> = model
         .game
         .worlds
         .iter()
-        .enumerate() // Get the index of each world
+        .enumerate()
         .map(|(index, world)| {
-            let lock_str = if index > model.save_file.saves[0].level {
+            let progress = model.level_progress(index);
+            let mark = if !is_unlocked(model, index) {
                 "🔒 "
+            } else if progress.is_some_and(|level| level.solved) {
+                "✓  "
+            } else if progress.is_some_and(|level| level.skipped) {
+                "↷  "
+            } else if progress.is_some_and(|level| level.in_progress.is_some()) {
+                "…  "
             } else {
                 "   "
             };
-            if index == selected_world_index {
-                format!("** {}{}    **\n", lock_str, world.name) // Highlight the selected world
-            } else {
-                format!("   {}{}\n", lock_str, world.name) // Normal formatting for other worlds
-            }
+            ListItem::new(format!("{}{}", mark, world.name))
         })
         .collect();
 
-    // TODO: @Lee I tried to do some pretty simple logic but i dont think its in a rusty way. im going to ignore this problem
-    // for now lol
-    // Print 11 rows, with selected in the middle, or otherwise if needed for shorter lists
-    let num_of_rows = std::cmp::min(11, world_names.len());
+    let unlock = match model.save_file.options.unlock_for(&model.game.collection) {
+        Unlock::Linear => "one at a time".to_string(),
+        Unlock::Window(open) => format!("{} at a time", open),
+        Unlock::Open => "all open".to_string(),
+    };
+    let title = format!("{} ({})", model.game.collection.title, unlock);
+    let list_block = Block::bordered().title(title);
+    model.level_select.page_size = list_block.inner(list_area).height.max(1) as usize;
+    model
+        .level_select
+        .list_state
+        .select(Some(model.game.world_index));
+    let list = List::new(items)
+        .block(list_block)
+        .highlight_style(Style::default().add_modifier(Modifier::REVERSED))
+        .highlight_symbol("» ");
+    frame.render_stateful_widget(list, list_area, &mut model.level_select.list_state);
+
+    // A tiny map of the highlighted level, one cell per tile
+    let world = &model.game.worlds[model.game.world_index];
+    let preview_block = Block::bordered().title(world.name.clone());
+    let preview_inner = preview_block.inner(preview_area);
+    frame.render_widget(preview_block, preview_area);
+    frame.render_widget(GameWindow::new(world.clone(), Zoom::Far), preview_inner);
 
-    let end_index = (selected_world_index + num_of_rows).min(world_names.len());
+    let select = &model.level_select;
+    let keys = |action| model.keymap.key(Screen::Levels, action);
+    let prompt = if let Some(search) = &select.search {
+        format!(
+            " Search: {}_   (Enter to keep, Esc to cancel, ↑↓ for more)",
+            search
+        )
+    } else if !select.jump.is_empty() {
+        format!(" Go to level: {}_   (Enter to jump)", select.jump)
+    } else if let Some(moves) = model
+        .level_progress(model.game.world_index)
+        .and_then(|level| level.in_progress.as_ref())
+    {
+        format!(
+            " {} Resume ({} moves)   {} Start Fresh   {} Mirrored   0-9 Go to Level   {} Search   {} Replay",
+            keys(Action::Play),
+            moves.len(),
+            keys(Action::StartFresh),
+            keys(Action::PlayMirrored),
+            keys(Action::Search),
+            keys(Action::Replay)
+        )
+    } else {
+        format!(
+            " {}{} {} {} {} {}   {} Mirrored   0-9 Go to Level   {} Search   {} Replay   {} Skip   {} Unlock",
+            keys(Action::Up),
+            keys(Action::Down),
+            keys(Action::PageUp),
+            keys(Action::PageDown),
+            keys(Action::First),
+            keys(Action::Last),
+            keys(Action::PlayMirrored),
+            keys(Action::Search),
+            keys(Action::Replay),
+            keys(Action::Skip),
+            keys(Action::Unlock)
+        )
+    };
+    frame.render_widget(Paragraph::new(prompt), prompt_area);
+}
 
-    // Get the slice of names from the vector
-    let world_names_slice = &world_names[selected_world_index..end_index];
-    // Join the slice into a single string
-    let world_names_joined = world_names_slice.join("");
+/// How many unfinished levels a sliding window opens when first switched to
+const DEFAULT_WINDOW: usize = 3;
 
-    // Push the concatenated string to view_text
-    view_text.push_str(&world_names_joined);
+/// Whether the player is allowed to pick the level at `index` yet
+pub fn is_unlocked(model: &Model, index: usize) -> bool {
+    let options = &model.save_file.options;
+    let collection = &model.game.collection;
+    options.develop
+        || model.save_file.current().is_unlocked(
+            collection,
+            options.unlock_for(collection),
+            index,
+        )
+}
+
+/// Moves the cursor to `index`, or as close to it as the unlocked levels allow
+fn select_level(model: &mut Model, index: usize) {
+    let last = model.game.worlds.len() - 1;
+    let mut index = index.min(last);
+    while index > 0 && !is_unlocked(model, index) {
+        index -= 1;
+    }
+    if index != model.game.world_index {
+        model.game.change_level(index);
+    }
+}
 
-    frame.render_widget(Paragraph::new(view_text), frame.area());
+/// The next level after (or before, going `backwards`) the cursor whose title contains
+/// `search`, wrapping around the end of the list
+fn find_level(
+    model: &Model,
+    search: &str,
+    from: usize,
+    backwards: bool,
+) -> Option<usize> {
+    let search = search.to_lowercase();
+    let count = model.game.worlds.len();
+    (0..count)
+        .map(|step| {
+            if backwards {
+                (from + count - step) % count
+            } else {
+                (from + step) % count
+            }
+        })
+        .find(|&index| {
+            is_unlocked(model, index)
+                && model.game.worlds[index]
+                    .name
+                    .to_lowercase()
+                    .contains(&search)
+        })
 }
 
 pub fn update(model: &mut Model, msg: LevelSelectAction) -> Option<LevelSelectAction> {
+    let current = model.game.world_index;
+    let page = model.level_select.page_size.max(1);
     match msg {
         LevelSelectAction::Quit => {
-            model.running_state = RunningState::Menu;
+            model.running_state = RunningState::CollectionSelect;
         }
         LevelSelectAction::Select => {
-            model.running_state = RunningState::Game;
+            if model.level_select.search.take().is_some() {
+                // Enter while searching just keeps the match
+            } else if !model.level_select.jump.is_empty() {
+                let jump = std::mem::take(&mut model.level_select.jump);
+                if let Ok(number) = jump.parse::<usize>() {
+                    select_level(model, number.saturating_sub(1));
+                }
+            } else {
+                model.game.symmetry = Symmetry::IDENTITY;
+                soko_game::start_level(model, false);
+            }
+        }
+        LevelSelectAction::StartFresh => {
+            model.game.symmetry = Symmetry::IDENTITY;
+            soko_game::start_level(model, true);
+        }
+        LevelSelectAction::PlayMirrored => {
+            model.game.symmetry = Symmetry::MIRROR;
+            soko_game::start_level(model, false);
+        }
+        LevelSelectAction::ToggleSkip => {
+            // Solved levels are already done, there's nothing to skip
+            if !model
+                .save_file
+                .current()
+                .is_solved(&model.game.collection, current)
+            {
+                let level = model.level_progress_mut(current);
+                level.skipped = !level.skipped;
+            }
+        }
+        LevelSelectAction::CycleUnlock => {
+            let collection = &model.game.collection;
+            let unlock = match model.save_file.options.unlock_for(collection) {
+                Unlock::Linear => Unlock::Window(DEFAULT_WINDOW),
+                Unlock::Window(_) => Unlock::Open,
+                Unlock::Open => Unlock::Linear,
+            };
+            model
+                .save_file
+                .options
+                .unlock
+                .insert(collection.name.clone(), unlock);
+            // Going stricter can lock the level under the cursor
+            select_level(model, current);
+        }
+        LevelSelectAction::Replay => {
+            let solution = model
+                .level_progress(model.game.world_index)
+                .and_then(|level| level.solution.as_ref());
+            let world = &model.game.worlds[model.game.world_index];
+            // A stored solution always replays, it was recorded from real moves
+            if let Ok(mut replay) = replay::load(
+                world,
+                solution.map_or("", |s| s.as_str()),
+                RunningState::LevelSelect,
+            ) {
+                if solution.is_none() {
+                    replay.message =
+                        Some("No solution stored yet, paste one in!".to_string());
+                }
+                model.replay = Some(replay);
+                model.running_state = RunningState::Replay;
+            }
         }
         LevelSelectAction::Up => {
-            model.game.decrement_level();
+            if let Some(search) = model.level_select.search.clone() {
+                let from = current + model.game.worlds.len() - 1;
+                if let Some(index) = find_level(model, &search, from, true) {
+                    select_level(model, index);
+                }
+            } else {
+                model.game.decrement_level();
+            }
         }
         LevelSelectAction::Down => {
-            // Move cursor down if you have unlocked that level already
-            if model.save_file.saves[0].level > model.game.world_index
-                || cfg!(feature = "develop")
+            if let Some(search) = model.level_select.search.clone() {
+                if let Some(index) = find_level(model, &search, current + 1, false) {
+                    select_level(model, index);
+                }
+            } else if current + 1 < model.game.worlds.len()
+                && is_unlocked(model, current + 1)
             {
-                model.game.increment_level();
+                // Move cursor down if you have unlocked that level already
+                select_level(model, current + 1);
+            }
+        }
+        LevelSelectAction::PageUp => select_level(model, current.saturating_sub(page)),
+        LevelSelectAction::PageDown => select_level(model, current + page),
+        LevelSelectAction::First => select_level(model, 0),
+        LevelSelectAction::Last => select_level(model, model.game.worlds.len() - 1),
+        LevelSelectAction::JumpDigit(digit) => model.level_select.jump.push(digit),
+        LevelSelectAction::StartSearch => {
+            model.level_select.jump.clear();
+            model.level_select.search = Some(String::new());
+            model.level_select.search_origin = current;
+        }
+        LevelSelectAction::SearchChar(ch) => {
+            if let Some(search) = &mut model.level_select.search {
+                search.push(ch);
+                let search = search.clone();
+                // Matches from the level the search started on, so typing more letters
+                // narrows the match down rather than skipping ahead
+                let origin = model.level_select.search_origin;
+                if let Some(index) = find_level(model, &search, origin, false) {
+                    select_level(model, index);
+                }
+            }
+        }
+        LevelSelectAction::Backspace => {
+            if let Some(search) = &mut model.level_select.search {
+                search.pop();
+            } else {
+                model.level_select.jump.pop();
             }
         }
+        LevelSelectAction::Cancel => {
+            if model.level_select.search.take().is_some() {
+                select_level(model, model.level_select.search_origin);
+            }
+            model.level_select.jump.clear();
+        }
     };
     None
 }
 
-pub fn handle_event(_: &Model) -> io::Result<Option<LevelSelectAction>> {
+pub fn handle_event(model: &Model) -> io::Result<Option<LevelSelectAction>> {
     if event::poll(Duration::from_millis(250))? {
         if let Event::Key(key) = event::read()? {
             if key.kind == event::KeyEventKind::Press {
-                return Ok(handle_key(key));
+                return Ok(handle_key(model, key));
             }
         }
     }
     Ok(None)
 }
 
-// Handle todo: repeating same key logic is kinda clunky. the idea of "up" is the same across the board bro
-pub fn handle_key(key: event::KeyEvent) -> Option<LevelSelectAction> {
-    match key.code {
-        KeyCode::Esc => Some(LevelSelectAction::Quit),
-        KeyCode::Enter | KeyCode::Char(' ') => Some(LevelSelectAction::Select),
-        KeyCode::Up | KeyCode::Char('w') | KeyCode::Char('W') => {
-            Some(LevelSelectAction::Up)
-        }
-        KeyCode::Down | KeyCode::Char('s') | KeyCode::Char('S') => {
-            Some(LevelSelectAction::Down)
+pub fn handle_key(model: &Model, key: event::KeyEvent) -> Option<LevelSelectAction> {
+    let select = &model.level_select;
+    // Typing a search takes over the letter keys
+    if select.search.is_some() {
+        return match key.code {
+            KeyCode::Esc => Some(LevelSelectAction::Cancel),
+            KeyCode::Enter => Some(LevelSelectAction::Select),
+            KeyCode::Backspace => Some(LevelSelectAction::Backspace),
+            KeyCode::Up => Some(LevelSelectAction::Up),
+            KeyCode::Down => Some(LevelSelectAction::Down),
+            KeyCode::Char(ch) => Some(LevelSelectAction::SearchChar(ch)),
+            _ => None,
+        };
+    }
+
+    let action = model.keymap.action(Screen::Levels, &key);
+    match (action, key.code) {
+        // Backing out of a level number being typed comes before leaving
+        (Some(Action::Back), _) if !select.jump.is_empty() => {
+            Some(LevelSelectAction::Cancel)
+        }
+        (Some(Action::Back), _) => Some(LevelSelectAction::Quit),
+        (Some(Action::Play), _) => Some(LevelSelectAction::Select),
+        (Some(Action::Replay), _) => Some(LevelSelectAction::Replay),
+        (Some(Action::StartFresh), _) => Some(LevelSelectAction::StartFresh),
+        (Some(Action::PlayMirrored), _) => Some(LevelSelectAction::PlayMirrored),
+        (Some(Action::Skip), _) => Some(LevelSelectAction::ToggleSkip),
+        (Some(Action::Unlock), _) => Some(LevelSelectAction::CycleUnlock),
+        (Some(Action::Search), _) => Some(LevelSelectAction::StartSearch),
+        (Some(Action::Up), _) => Some(LevelSelectAction::Up),
+        (Some(Action::Down), _) => Some(LevelSelectAction::Down),
+        (Some(Action::PageUp), _) => Some(LevelSelectAction::PageUp),
+        (Some(Action::PageDown), _) => Some(LevelSelectAction::PageDown),
+        (Some(Action::First), _) => Some(LevelSelectAction::First),
+        (Some(Action::Last), _) => Some(LevelSelectAction::Last),
+        (_, KeyCode::Backspace) => Some(LevelSelectAction::Backspace),
+        (_, KeyCode::Char(ch)) if ch.is_ascii_digit() => {
+            Some(LevelSelectAction::JumpDigit(ch))
         }
         _ => None,
     }
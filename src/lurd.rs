@@ -0,0 +1,129 @@
+/*!
+ * Description:
+ *
+ * Reading and writing move sequences in LURD notation, the format Sokoban programs use to
+ * publish solutions. Each move is one of `l`, `u`, `r` or `d`, upper case when the move
+ * pushed (or, in reverse mode, pulled) a box.
+ *
+ * Usage:
+ *
+ *   let ch = lurd::to_char(Direction::Up, true); // 'U'
+ *   let (direction, pushed) = lurd::from_char('l').unwrap();
+ *   let moves = lurd::expand("3r2(Ul)")?; // "rrrUlUl"
+ */
+
+use std::iter::Peekable;
+
+use crate::types::Direction;
+
+pub fn to_char(direction: Direction, push: bool) -> char {
+    let ch = match direction {
+        Direction::Left => 'l',
+        Direction::Up => 'u',
+        Direction::Right => 'r',
+        Direction::Down => 'd',
+    };
+    if push {
+        ch.to_ascii_uppercase()
+    } else {
+        ch
+    }
+}
+
+/// The direction for a LURD character, and whether it was a push
+pub fn from_char(ch: char) -> Option<(Direction, bool)> {
+    let direction = match ch.to_ascii_lowercase() {
+        'l' => Direction::Left,
+        'u' => Direction::Up,
+        'r' => Direction::Right,
+        'd' => Direction::Down,
+        _ => return None,
+    };
+    Some((direction, ch.is_ascii_uppercase()))
+}
+
+/// Parses a LURD string into directions. Whitespace (solutions are often wrapped over
+/// several lines) is ignored.
+pub fn parse(lurd: &str) -> Result<Vec<Direction>, String> {
+    lurd.chars()
+        .filter(|ch| !ch.is_whitespace())
+        .map(|ch| {
+            from_char(ch)
+                .map(|(direction, _)| direction)
+                .ok_or_else(|| format!("'{}' is not a LURD move", ch))
+        })
+        .collect()
+}
+
+/// The longest solution `expand` will produce. Counts come from files found online, and a
+/// stray huge one shouldn't take all the memory there is.
+pub const MAX_MOVES: usize = 1_000_000;
+
+/// Undoes the run length encoding some programs publish solutions in, where a count
+/// repeats the move or bracketed group after it: `3r2U` is `rrrUU` and `2(lU)` is
+/// `lUlU`. Whitespace is dropped, and plain LURD comes back as it was.
+pub fn expand(text: &str) -> Result<String, String> {
+    let mut chars = text.chars().filter(|ch| !ch.is_whitespace()).peekable();
+    let moves = expand_group(&mut chars)?;
+    match chars.next() {
+        Some(ch) => Err(format!("'{}' doesn't close a group", ch)),
+        None => Ok(moves),
+    }
+}
+
+/// Expands up to the end of the text or the `)` closing the group, which is left unread
+fn expand_group(
+    chars: &mut Peekable<impl Iterator<Item = char>>,
+) -> Result<String, String> {
+    let mut moves = String::new();
+    while let Some(&ch) = chars.peek() {
+        if ch == ')' {
+            break;
+        }
+        let mut count: Option<usize> = None;
+        while let Some(digit) = chars.peek().and_then(|ch| ch.to_digit(10)) {
+            let digits = count
+                .unwrap_or(0)
+                .checked_mul(10)
+                .and_then(|count| count.checked_add(digit as usize))
+                .ok_or_else(too_long)?;
+            count = Some(digits);
+            chars.next();
+        }
+        // No count means once, but a written 0 is a mistake rather than a way to
+        // leave a move out
+        let count = match count {
+            None => 1,
+            Some(0) => return Err("A count of 0 repeats nothing".to_string()),
+            Some(count) => count,
+        };
+        let repeated = match chars.next() {
+            Some('(') => {
+                let group = expand_group(chars)?;
+                if chars.next() != Some(')') {
+                    return Err("A group is missing its ')'".to_string());
+                }
+                group
+            }
+            Some(ch) if from_char(ch).is_some() => ch.to_string(),
+            Some(ch) => return Err(format!("'{}' is not a LURD move", ch)),
+            None => return Err("The solution ends in a count".to_string()),
+        };
+        repeated
+            .len()
+            .checked_mul(count)
+            .and_then(|length| length.checked_add(moves.len()))
+            .filter(|&length| length <= MAX_MOVES)
+            .ok_or_else(too_long)?;
+        moves.push_str(&repeated.repeat(count));
+    }
+    Ok(moves)
+}
+
+fn too_long() -> String {
+    format!("The solution is longer than {} moves", MAX_MOVES)
+}
+
+pub fn push_count(lurd: &str) -> usize {
+    lurd.chars().filter(|ch| ch.is_ascii_uppercase()).count()
+}
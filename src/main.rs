@@ -1,75 +1,234 @@
-use serde::Serialize;
-use std::fs::File;
-use std::io::{self, Read, Write};
+use std::io;
+use std::path::Path;
 
+use storage::save_toml_file;
+
+mod board_layout;
+mod board_layout_tests;
+mod cli;
+mod cli_tests;
+mod clipboard;
+mod clipboard_tests;
+mod collection;
+mod collection_select;
+mod collection_tests;
 mod colors;
 mod copy_text;
+mod dedup;
+mod dedup_tests;
+mod generator;
+mod generator_tests;
+mod import;
+mod import_tests;
+mod keymap;
+mod keymap_tests;
 mod level_select;
+mod lurd;
 mod menu;
+mod profiles;
+mod profiles_tests;
+mod recording;
+mod recording_tests;
 mod render;
 mod render_tests;
+mod replay;
+mod replay_tests;
+mod reverse;
+mod reverse_tests;
+mod save_schema;
+mod save_schema_tests;
+mod snapshot;
+mod snapshot_tests;
 mod soko_game;
+mod soko_game_tests;
 mod soko_loader;
 mod soko_loader_tests;
+mod soko_state;
+mod soko_state_tests;
+mod solver;
+mod solver_tests;
 mod sprites;
+mod storage;
+mod storage_tests;
+mod summary;
+#[cfg(test)]
+mod test_util;
+mod transform;
+mod transform_tests;
 mod types;
+mod victory;
+mod victory_tests;
 
-fn read_file(filename: &str) -> Result<String, io::Error> {
-    let mut file = File::open(filename)?;
-    let mut contents = String::new();
-    file.read_to_string(&mut contents)?;
-    Ok(contents)
-}
-
-fn save_toml_file<T: Serialize>(filename: &str, toml: &T) -> Result<(), io::Error> {
-    // Serialize the struct to a TOML string
-    let toml_string = toml::to_string(&toml).unwrap();
+fn main() -> io::Result<()> {
+    let args: Vec<String> = std::env::args().skip(1).collect();
+    let cli::Args { command, develop } = match cli::parse(&args) {
+        Ok(args) => args,
+        Err(e) => {
+            eprintln!("{}", e);
+            std::process::exit(2);
+        }
+    };
 
-    // Write the serialized string to a file
-    let mut file = File::create(filename)?;
-    file.write_all(toml_string.as_bytes())?;
-    Ok(())
-}
+    // Rendering to a file doesn't need the game, or the terminal
+    if let cli::Command::Render {
+        file,
+        level,
+        moves,
+        zoom,
+        output,
+    } = &command
+    {
+        if let Err(e) = snapshot::run(file, *level, moves, *zoom, output.as_deref()) {
+            eprintln!("{}", e);
+            std::process::exit(1);
+        }
+        return Ok(());
+    }
+    if let cli::Command::Record {
+        file,
+        level,
+        moves,
+        zoom,
+        speed,
+        output,
+    } = &command
+    {
+        let output = output.as_deref();
+        if let Err(e) = recording::run(file, *level, moves, *zoom, *speed, output) {
+            eprintln!("{}", e);
+            std::process::exit(1);
+        }
+        return Ok(());
+    }
 
-fn main() -> io::Result<()> {
-    tui::install_panic_hook();
-    let save_filename = "saves.toml";
-    let mut terminal = tui::init_terminal()?;
+    if let cli::Command::Dedup { files } = &command {
+        match dedup::run(files) {
+            Ok(report) => print!("{}", report),
+            Err(e) => {
+                eprintln!("{}", e);
+                std::process::exit(1);
+            }
+        }
+        return Ok(());
+    }
+    if let cli::Command::Import { levels, solutions } = &command {
+        let save_path = storage::save_path();
+        let (mut saves, notice) = load_save_file(&save_path);
+        if let Some(notice) = notice {
+            eprintln!("{}", notice);
+        }
+        match import::run(levels, solutions, &mut saves) {
+            Ok(report) => {
+                for line in report {
+                    println!("{}", line);
+                }
+                save_toml_file(&save_path, &saves)?;
+            }
+            Err(e) => {
+                eprintln!("{}", e);
+                std::process::exit(1);
+            }
+        }
+        return Ok(());
+    }
 
-    let ban_filename = "./resources/levels/micro2.ban";
+    // The new levels are played straight away, if they're somewhere the game looks
+    let mut generated = None;
+    if let cli::Command::Generate { settings, output } = &command {
+        match generator::run(settings, output.as_deref()) {
+            Ok(path) => {
+                let plural = if settings.count == 1 { "" } else { "s" };
+                println!(
+                    "Wrote {} level{} to {}",
+                    settings.count,
+                    plural,
+                    path.display()
+                );
+                generated = Some(path);
+            }
+            Err(e) => {
+                eprintln!("{}", e);
+                std::process::exit(1);
+            }
+        }
+    }
 
-    // TODO: actually handle errors here
-    let worlds = read_file(ban_filename)
-        .map(|contents| soko_loader::parse_sokoban_worlds(&contents).unwrap())
-        .unwrap();
+    tui::install_panic_hook();
+    let save_path = storage::save_path();
 
-    let saves: Option<types::SaveFile> = read_file(save_filename)
-        .map(|contents| toml::from_str(&contents).unwrap())
-        .ok();
+    let (mut saves, save_notice) = load_save_file(&save_path);
+    let (keymap, keymap_notice) = keymap::load();
+    let notices: Vec<String> = save_notice.into_iter().chain(keymap_notice).collect();
+    saves.options.develop = develop;
 
-    let mut current_world_i = 0;
-    let saves = match saves {
-        Some(saves) => {
-            current_world_i = saves.saves[0].level;
-            saves
+    let (files, problems) = collection::scan(&collection::level_dirs());
+    for file in files.iter() {
+        saves.adopt_collection(&file.collection);
+    }
+    if let Some(path) = &generated {
+        let looked_in = collection::level_dirs().iter().any(|dir| {
+            path.parent().and_then(|parent| parent.canonicalize().ok())
+                == dir.canonicalize().ok()
+        });
+        if !looked_in {
+            return Ok(());
         }
-        None => types::SaveFile::new(),
-    };
-    let game_window = types::GameWindow {
-        world: worlds[current_world_i].clone(),
-        zoom: types::Zoom::Middle,
-        debug: Vec::new(),
+        saves.collection = collection::name_of(path);
+    }
+    // Open whichever collection was played last
+    let Some(file) = [saves.collection.as_str(), collection::DEFAULT]
+        .iter()
+        .find_map(|name| files.iter().find(|file| file.collection.name == *name))
+        .or(files.first())
+    else {
+        eprintln!("No level files found in ./resources/levels");
+        for problem in problems {
+            eprintln!("{}", problem);
+        }
+        std::process::exit(1);
     };
-    let mut model = types::Model {
-        running_state: types::RunningState::Menu,
-        game: types::Game {
-            history: Vec::new(),
-            window: game_window,
-            worlds: worlds.clone(),
-            world_index: current_world_i,
-        },
-        save_file: saves,
+    let collection = file.collection.clone();
+    let worlds = file.worlds.clone();
+    let current_world_i = saves
+        .current()
+        .first_unfinished(&collection)
+        .min(worlds.len() - 1);
+    let mut game = types::Game::new(worlds, collection, current_world_i);
+    game.window.animate = saves.options.animations;
+    let mut model = types::Model::new(game, saves);
+    model.collection_select = types::CollectionSelect {
+        files,
+        problems,
+        ..Default::default()
     };
+    model.keymap = keymap;
+    model.notice = (!notices.is_empty()).then(|| notices.join("\n\n"));
+
+    if generated.is_some() {
+        model.running_state = types::RunningState::LevelSelect;
+    }
+
+    if let cli::Command::Replay { level, solution } = command {
+        if level > model.game.worlds.len() {
+            eprintln!("There are only {} levels", model.game.worlds.len());
+            std::process::exit(2);
+        }
+        model.game.change_level(level - 1);
+        match replay::load(
+            &model.game.worlds[level - 1],
+            &solution,
+            types::RunningState::LevelSelect,
+        ) {
+            Ok(replay) => model.replay = Some(replay),
+            Err(e) => {
+                eprintln!("{}", e);
+                std::process::exit(2);
+            }
+        }
+        model.running_state = types::RunningState::Replay;
+    }
+
+    let mut terminal = tui::init_terminal()?;
 
     loop {
         match model.running_state {
@@ -92,35 +251,114 @@ fn main() -> io::Result<()> {
                 // Handle events and map to a Message
                 let mut current_msg = soko_game::handle_event(&mut model)?;
 
-                // When you win a level, move to the next level!
-                // XXX: This has to happen before the while loop below. Why?
+                // Winning records the level as solved and shows how it went
                 if let Some(types::GameAction::Win) = current_msg {
-                    // When we win a game we then (try to) go to the next level in the list!
-                    model.game.increment_level();
-
-                    // Update latest level unlocked if you just did that, anyway
-                    if model.game.world_index > model.save_file.saves[0].level {
-                        model.save_file.saves[0].level = model.game.world_index;
-                    }
-                    save_toml_file(save_filename, &model.save_file)?;
+                    victory::open(&mut model);
+                    save_toml_file(&save_path, &model.save_file)?;
                     continue;
                 }
 
+                // Leaving the level stores how far you got with it
+                let changes_save_file = matches!(
+                    current_msg,
+                    Some(
+                        types::GameAction::ToggleAnimation
+                            | types::GameAction::Quit
+                            | types::GameAction::Paste(_)
+                    )
+                );
+
                 // Process updates as long as they return a non-None message
                 while current_msg.is_some() {
                     current_msg = soko_game::update(&mut model, current_msg.unwrap());
                 }
+
+                if changes_save_file {
+                    save_toml_file(&save_path, &model.save_file)?;
+                }
+            }
+            types::RunningState::Replay => {
+                terminal.draw(|f| replay::view(&mut model, f))?;
+                // Handle events and map to a Message
+                let mut current_msg = replay::handle_event(&model)?;
+
+                // Process updates as long as they return a non-None message
+                while current_msg.is_some() {
+                    current_msg = replay::update(&mut model, current_msg.unwrap());
+                }
+            }
+            types::RunningState::Victory => {
+                terminal.draw(|f| victory::view(&mut model, f))?;
+                // Handle events and map to a Message
+                let mut current_msg = victory::handle_event(&model)?;
+
+                // Process updates as long as they return a non-None message
+                while current_msg.is_some() {
+                    current_msg = victory::update(&mut model, current_msg.unwrap());
+                }
+            }
+            types::RunningState::Summary => {
+                terminal.draw(|f| summary::view(&mut model, f))?;
+                // Handle events and map to a Message
+                let mut current_msg = summary::handle_event(&model)?;
+
+                // Process updates as long as they return a non-None message
+                while current_msg.is_some() {
+                    current_msg = summary::update(&mut model, current_msg.unwrap());
+                }
+            }
+            types::RunningState::Profiles => {
+                terminal.draw(|f| profiles::view(&mut model, f))?;
+                // Handle events and map to a Message
+                let mut current_msg = profiles::handle_event(&model)?;
+                let changes_save_file = current_msg
+                    .as_ref()
+                    .is_some_and(|msg| msg.changes_save_file());
+
+                // Process updates as long as they return a non-None message
+                while current_msg.is_some() {
+                    current_msg = profiles::update(&mut model, current_msg.unwrap());
+                }
+
+                if changes_save_file {
+                    save_toml_file(&save_path, &model.save_file)?;
+                }
+            }
+            types::RunningState::CollectionSelect => {
+                terminal.draw(|f| collection_select::view(&mut model, f))?;
+                // Handle events and map to a Message
+                let mut current_msg = collection_select::handle_event(&model)?;
+                let changes_save_file = current_msg
+                    .as_ref()
+                    .is_some_and(|msg| msg.changes_save_file());
+
+                // Process updates as long as they return a non-None message
+                while current_msg.is_some() {
+                    current_msg =
+                        collection_select::update(&mut model, current_msg.unwrap());
+                }
+
+                if changes_save_file {
+                    save_toml_file(&save_path, &model.save_file)?;
+                }
             }
             types::RunningState::LevelSelect => {
                 terminal.draw(|f| level_select::view(&mut model, f))?;
                 // Handle events and map to a Message
                 let mut current_msg = level_select::handle_event(&model)?;
+                let changes_save_file = current_msg
+                    .as_ref()
+                    .is_some_and(|msg| msg.changes_save_file());
 
                 // Process updates as long as they return a non-None message
                 while current_msg.is_some() {
                     current_msg =
                         level_select::update(&mut model, current_msg.unwrap());
                 }
+
+                if changes_save_file {
+                    save_toml_file(&save_path, &model.save_file)?;
+                }
             }
         }
     }
@@ -129,10 +367,38 @@ fn main() -> io::Result<()> {
     Ok(())
 }
 
+/// Loads the save file, or starts a fresh one if there isn't one yet.
+///
+/// A save that can't be read is set aside rather than crashing the game or being
+/// overwritten, and the returned notice tells the player where it went.
+fn load_save_file(path: &Path) -> (types::SaveFile, Option<String>) {
+    let error = match storage::read_save_file(path) {
+        Ok(contents) => match save_schema::parse_save_file(&contents) {
+            Ok(saves) => return (saves, None),
+            Err(e) => e,
+        },
+        Err(e) if e.kind() == io::ErrorKind::NotFound => {
+            return (types::SaveFile::new(), None);
+        }
+        Err(e) => e.to_string(),
+    };
+
+    // If it can't be moved the next save still copies it to the backup first
+    let kept_at =
+        storage::set_aside(path).unwrap_or_else(|_| storage::backup_path(path));
+    let notice = format!(
+        "Your save file couldn't be loaded: {}.\nIt was kept as {} and a new one started.",
+        error,
+        kept_at.display()
+    );
+    (types::SaveFile::new(), Some(notice))
+}
+
 mod tui {
     use ratatui::{
         backend::{Backend, CrosstermBackend},
         crossterm::{
+            event::{DisableBracketedPaste, EnableBracketedPaste},
             terminal::{
                 disable_raw_mode, enable_raw_mode, EnterAlternateScreen,
                 LeaveAlternateScreen,
@@ -147,11 +413,14 @@ mod tui {
     pub fn init_terminal() -> io::Result<Terminal<impl Backend>> {
         enable_raw_mode()?;
         stdout().execute(EnterAlternateScreen)?;
+        // Lets pasted text (like a LURD solution) arrive as a single event
+        stdout().execute(EnableBracketedPaste)?;
         let terminal = Terminal::new(CrosstermBackend::new(stdout()))?;
         Ok(terminal)
     }
 
     pub fn restore_terminal() -> io::Result<()> {
+        stdout().execute(DisableBracketedPaste)?;
         stdout().execute(LeaveAlternateScreen)?;
         disable_raw_mode()?;
         Ok(())
@@ -160,6 +429,7 @@ mod tui {
     pub fn install_panic_hook() {
         let original_hook = panic::take_hook();
         panic::set_hook(Box::new(move |panic_info| {
+            stdout().execute(DisableBracketedPaste).unwrap();
             stdout().execute(LeaveAlternateScreen).unwrap();
             disable_raw_mode().unwrap();
             original_hook(panic_info);
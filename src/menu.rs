@@ -1,22 +1,42 @@
-use crate::copy_text;
+use crate::keymap::{Action, Screen};
 use crate::types::{MenuAction, Model, RunningState, SaveFile};
+use crate::{collection_select, copy_text, storage};
+use std::io;
 use std::time::Duration;
-use std::{fs, io};
 
 use ratatui::{
-    crossterm::event::{self, Event, KeyCode},
+    crossterm::event::{self, Event},
     widgets::Paragraph,
     Frame,
 };
 
-pub fn view(_model: &mut Model, frame: &mut Frame) {
-    frame.render_widget(Paragraph::new(copy_text::MENU.to_string()), frame.area());
+pub fn view(model: &mut Model, frame: &mut Frame) {
+    let mut text = format!(
+        "{}\nCONTROLS:\n{}{}\n{}\nPROFILE: {}  (Press {} to Change)",
+        copy_text::MENU,
+        model.keymap.help(Screen::Menu),
+        model.keymap.help(Screen::Game),
+        copy_text::RULES,
+        model.save_file.current().name,
+        model.keymap.key(Screen::Menu, Action::Profiles),
+    );
+    if let Some(notice) = &model.notice {
+        text.push_str(&format!("\n\n{}", notice));
+    }
+    frame.render_widget(Paragraph::new(text), frame.area());
 }
 
 pub fn update(model: &mut Model, msg: MenuAction) -> Option<MenuAction> {
+    // The notice has been seen once the player does anything
+    model.notice = None;
     match msg {
         MenuAction::StartGame => {
-            model.running_state = RunningState::LevelSelect;
+            collection_select::refresh(model);
+            model.running_state = RunningState::CollectionSelect;
+        }
+        MenuAction::Profiles => {
+            model.profile_select.selected = model.save_file.active;
+            model.running_state = RunningState::Profiles;
         }
         MenuAction::Quit => {
             // You can handle cleanup and exit here
@@ -24,7 +44,9 @@ pub fn update(model: &mut Model, msg: MenuAction) -> Option<MenuAction> {
         }
         MenuAction::EraseSaveData => {
             // When dev/user deletes a save file, put them back on world 1
-            delete_save_file();
+            if let Err(e) = storage::delete_save_file(&storage::save_path()) {
+                println!("{}", e)
+            }
             model.save_file = SaveFile::new();
             model.game.change_level(0);
         }
@@ -32,33 +54,27 @@ pub fn update(model: &mut Model, msg: MenuAction) -> Option<MenuAction> {
     None
 }
 
-fn delete_save_file() {
-    let save_file = "saves.toml";
-    if let Err(e) = fs::remove_file(save_file) {
-        println!("{}", e)
-    }
-}
-
 /// Convert Event to Message
 ///
 /// We don't need to pass in a `model` to this function in this example
 /// but you might need it as your project evolves
-pub fn handle_event(_: &Model) -> io::Result<Option<MenuAction>> {
+pub fn handle_event(model: &Model) -> io::Result<Option<MenuAction>> {
     if event::poll(Duration::from_millis(250))? {
         if let Event::Key(key) = event::read()? {
             if key.kind == event::KeyEventKind::Press {
-                return Ok(handle_key(key));
+                return Ok(handle_key(model, key));
             }
         }
     }
     Ok(None)
 }
 
-pub fn handle_key(key: event::KeyEvent) -> Option<MenuAction> {
-    match key.code {
-        KeyCode::Enter | KeyCode::Char(' ') => Some(MenuAction::StartGame),
-        KeyCode::Esc => Some(MenuAction::Quit),
-        KeyCode::Delete => Some(MenuAction::EraseSaveData),
+pub fn handle_key(model: &Model, key: event::KeyEvent) -> Option<MenuAction> {
+    match model.keymap.action(Screen::Menu, &key)? {
+        Action::Start => Some(MenuAction::StartGame),
+        Action::Profiles => Some(MenuAction::Profiles),
+        Action::Erase => Some(MenuAction::EraseSaveData),
+        Action::Quit => Some(MenuAction::Quit),
         _ => None,
     }
 }
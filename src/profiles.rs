@@ -0,0 +1,185 @@
+use std::io;
+use std::time::Duration;
+
+use crate::keymap::{Action, Screen};
+use crate::types::{Model, ProfileAction, ProfileEdit, RunningState};
+use ratatui::prelude::*;
+use ratatui::{
+    crossterm::event::{self, Event, KeyCode},
+    widgets::{Block, List, ListItem, ListState, Paragraph},
+    Frame,
+};
+
+const MAX_NAME_LENGTH: usize = 24;
+
+pub fn view(model: &mut Model, frame: &mut Frame) {
+    let [list_area, prompt_area] =
+        Layout::vertical([Constraint::Min(0), Constraint::Length(3)])
+            .areas(frame.area());
+
+    let save_file = &model.save_file;
+    let collection = &model.game.collection;
+    let items: Vec<ListItem> = save_file
+        .saves
+        .iter()
+        .enumerate()
+        .map(|(index, save)| {
+            let active = if index == save_file.active {
+                "▶ "
+            } else {
+                "  "
+            };
+            ListItem::new(format!(
+                "{}{:<width$}  Level {:>3}   {:>3} solved",
+                active,
+                save.name,
+                (save.first_unfinished(collection) + 1).min(collection.level_ids.len()),
+                save.solved_count(collection),
+                width = MAX_NAME_LENGTH,
+            ))
+        })
+        .collect();
+
+    let select = &model.profile_select;
+    let mut list_state = ListState::default().with_selected(Some(select.selected));
+    let list = List::new(items)
+        .block(Block::bordered().title("~PROFILES~"))
+        .highlight_style(Style::default().add_modifier(Modifier::REVERSED));
+    frame.render_stateful_widget(list, list_area, &mut list_state);
+
+    let prompt = if let Some(edit) = &select.editing {
+        format!("Name: {}_   (Enter to save, Esc to cancel)", edit.name)
+    } else if select.confirm_delete {
+        let name = &model.save_file.saves[select.selected].name;
+        format!("Delete {} and all of their progress? (Y/N)", name)
+    } else {
+        let keys = |action| model.keymap.key(Screen::Profiles, action);
+        format!(
+            "{} Play as   {} New   {} Rename   {} Delete   {} Back",
+            keys(Action::Switch),
+            keys(Action::New),
+            keys(Action::Rename),
+            keys(Action::Delete),
+            keys(Action::Back)
+        )
+    };
+    frame.render_widget(Paragraph::new(prompt).block(Block::bordered()), prompt_area);
+}
+
+pub fn update(model: &mut Model, msg: ProfileAction) -> Option<ProfileAction> {
+    let count = model.save_file.saves.len();
+    let select = &mut model.profile_select;
+    match msg {
+        ProfileAction::Back => model.running_state = RunningState::Menu,
+        ProfileAction::Up => select.selected = select.selected.saturating_sub(1),
+        ProfileAction::Down => select.selected = (select.selected + 1).min(count - 1),
+        ProfileAction::Switch => {
+            model.save_file.active = select.selected;
+            switch_level(model);
+            model.running_state = RunningState::Menu;
+        }
+        ProfileAction::New => {
+            select.editing = Some(ProfileEdit {
+                index: None,
+                name: format!("Player {}", count + 1),
+            });
+        }
+        ProfileAction::Rename => {
+            select.editing = Some(ProfileEdit {
+                index: Some(select.selected),
+                name: model.save_file.saves[select.selected].name.clone(),
+            });
+        }
+        ProfileAction::TypeChar(ch) => {
+            if let Some(edit) = &mut select.editing {
+                if edit.name.chars().count() < MAX_NAME_LENGTH {
+                    edit.name.push(ch);
+                }
+            }
+        }
+        ProfileAction::Backspace => {
+            if let Some(edit) = &mut select.editing {
+                edit.name.pop();
+            }
+        }
+        ProfileAction::Confirm => {
+            if let Some(edit) = select.editing.take() {
+                let name = edit.name.trim();
+                match edit.index {
+                    _ if name.is_empty() => {}
+                    Some(index) => model.save_file.saves[index].name = name.to_string(),
+                    None => select.selected = model.save_file.add_profile(name),
+                }
+            }
+        }
+        ProfileAction::Delete => select.confirm_delete = true,
+        ProfileAction::ConfirmDelete => {
+            select.confirm_delete = false;
+            let was_active = select.selected == model.save_file.active;
+            model.save_file.remove_profile(select.selected);
+            select.selected = select.selected.min(model.save_file.saves.len() - 1);
+            if was_active {
+                switch_level(model);
+            }
+        }
+        ProfileAction::Cancel => {
+            select.editing = None;
+            select.confirm_delete = false;
+        }
+    };
+    None
+}
+
+/// Puts the game on the first level the active profile hasn't solved
+fn switch_level(model: &mut Model) {
+    let level = model
+        .save_file
+        .current()
+        .first_unfinished(&model.game.collection);
+    model
+        .game
+        .change_level(level.min(model.game.worlds.len() - 1));
+}
+
+pub fn handle_event(model: &Model) -> io::Result<Option<ProfileAction>> {
+    if event::poll(Duration::from_millis(250))? {
+        if let Event::Key(key) = event::read()? {
+            if key.kind == event::KeyEventKind::Press {
+                return Ok(handle_key(model, key));
+            }
+        }
+    }
+    Ok(None)
+}
+
+pub fn handle_key(model: &Model, key: event::KeyEvent) -> Option<ProfileAction> {
+    let select = &model.profile_select;
+    if select.editing.is_some() {
+        return match key.code {
+            KeyCode::Enter => Some(ProfileAction::Confirm),
+            KeyCode::Esc => Some(ProfileAction::Cancel),
+            KeyCode::Backspace => Some(ProfileAction::Backspace),
+            KeyCode::Char(ch) => Some(ProfileAction::TypeChar(ch)),
+            _ => None,
+        };
+    }
+    if select.confirm_delete {
+        return match key.code {
+            KeyCode::Char('y') | KeyCode::Char('Y') => {
+                Some(ProfileAction::ConfirmDelete)
+            }
+            _ => Some(ProfileAction::Cancel),
+        };
+    }
+
+    match model.keymap.action(Screen::Profiles, &key)? {
+        Action::Back => Some(ProfileAction::Back),
+        Action::Switch => Some(ProfileAction::Switch),
+        Action::Up => Some(ProfileAction::Up),
+        Action::Down => Some(ProfileAction::Down),
+        Action::New => Some(ProfileAction::New),
+        Action::Rename => Some(ProfileAction::Rename),
+        Action::Delete => Some(ProfileAction::Delete),
+        _ => None,
+    }
+}
@@ -0,0 +1,86 @@
+#[cfg(test)]
+mod tests {
+    use crate::types::{Collection, SaveFile};
+
+    fn collection() -> Collection {
+        Collection {
+            name: "test".to_string(),
+            level_ids: (0..10).map(|index| index.to_string()).collect(),
+            ..Collection::default()
+        }
+    }
+
+    /// Marks the first `count` levels solved, as if the player had played through them
+    fn solve_levels(save_file: &mut SaveFile, count: usize) {
+        for index in 0..count {
+            save_file
+                .current_mut()
+                .level_mut(&collection(), index)
+                .solved = true;
+        }
+    }
+
+    fn names(save_file: &SaveFile) -> Vec<&str> {
+        save_file
+            .saves
+            .iter()
+            .map(|save| save.name.as_str())
+            .collect()
+    }
+
+    #[test]
+    fn test_profiles_keep_their_own_progress() {
+        let mut save_file = SaveFile::new();
+        solve_levels(&mut save_file, 7);
+
+        save_file.active = save_file.add_profile("Colton");
+        assert_eq!(save_file.current().name, "Colton");
+        assert_eq!(save_file.current().first_unfinished(&collection()), 0);
+
+        solve_levels(&mut save_file, 2);
+        assert_eq!(save_file.saves[0].first_unfinished(&collection()), 7);
+    }
+
+    #[test]
+    fn test_removing_a_profile_keeps_the_active_one() {
+        let mut save_file = SaveFile::new();
+        save_file.add_profile("B");
+        save_file.active = save_file.add_profile("C");
+
+        save_file.remove_profile(0);
+        assert_eq!(names(&save_file), vec!["B", "C"]);
+        assert_eq!(save_file.current().name, "C");
+
+        save_file.remove_profile(1);
+        assert_eq!(save_file.current().name, "B");
+    }
+
+    #[test]
+    fn test_removing_the_last_profile_starts_a_fresh_one() {
+        let mut save_file = SaveFile::new();
+        solve_levels(&mut save_file, 3);
+
+        save_file.remove_profile(0);
+        assert_eq!(save_file.saves.len(), 1);
+        assert_eq!(save_file.current().first_unfinished(&collection()), 0);
+    }
+
+    #[test]
+    fn test_old_save_files_load_into_the_first_profile() {
+        let mut save_file = crate::save_schema::parse_save_file(
+            "[[saves]]
+name = \"My Save\"
+level = 4
+",
+        )
+        .unwrap();
+        // Before collections there was only ever micro2
+        let micro2 = Collection {
+            name: "micro2".to_string(),
+            ..collection()
+        };
+        save_file.adopt_collection(&micro2);
+        assert_eq!(save_file.active, 0);
+        assert_eq!(save_file.current().first_unfinished(&micro2), 4);
+    }
+}
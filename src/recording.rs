@@ -0,0 +1,269 @@
+/*!
+ * Description:
+ *
+ * Records a solution being played, so it can be shared with people who don't have
+ * termban: an asciinema `.cast` file that plays back in a terminal or a browser, or an
+ * animated GIF.
+ *
+ * Each move is one frame, drawn through the same `GlyphCells` as `termban render` (see
+ * `snapshot`), and the last frame is held for a moment before the recording ends.
+ *
+ * Usage:
+ *
+ *   termban record levels.ban --level 3 --moves rrUdL --output solve.cast
+ *   termban record levels.ban --level 3 --moves rrUdL --speed 8 --output solve.gif
+ *
+ * Notes:
+ *
+ * Casts are asciicast version 2: a JSON header line, then one JSON array per frame
+ * holding its time in seconds, "o" for output, and the text written to the terminal.
+ *
+ * GIFs are written here too, LZW and all, rather than pulling in an image library. All
+ * the frames share one colour table, which the game's palette fits in with room to
+ * spare.
+ */
+
+use std::collections::HashMap;
+use std::fs;
+use std::path::Path;
+
+use crate::snapshot::{self, Pixels};
+use crate::types::{GlyphCells, RunningState, Zoom};
+use crate::{collection, replay};
+
+/// How many image pixels tall each pixel is drawn in a GIF, half as many across (see
+/// `snapshot`)
+const GIF_SCALE: usize = 4;
+
+/// How long the solved position stays up at the end, in seconds
+const HOLD: f64 = 2.0;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Format {
+    Cast,
+    Gif,
+}
+
+impl Format {
+    /// GIFs for a `.gif` file, anything else gets a cast
+    pub fn from_path(path: &Path) -> Format {
+        if path
+            .extension()
+            .is_some_and(|ext| ext.eq_ignore_ascii_case("gif"))
+        {
+            Format::Gif
+        } else {
+            Format::Cast
+        }
+    }
+}
+
+/// Runs `termban record`: plays `moves` on level `level` (1 based) of `file` at `speed`
+/// moves a second, and writes the recording to `output`, or a cast to stdout
+pub fn run(
+    file: &Path,
+    level: usize,
+    moves: &str,
+    zoom: Zoom,
+    speed: u64,
+    output: Option<&Path>,
+) -> Result<(), String> {
+    let (_, worlds) = collection::load(file)?;
+    let world = worlds.get(level - 1).ok_or_else(|| {
+        format!("{} only has {} levels", file.display(), worlds.len())
+    })?;
+    let worlds = replay::load(world, moves, RunningState::Menu)?.worlds;
+    let frames: Vec<GlyphCells> = worlds
+        .iter()
+        .map(|world| snapshot::glyph_cells(world, zoom))
+        .collect();
+
+    let Some(path) = output else {
+        print!("{}", to_cast(&world.name, &frames, speed));
+        return Ok(());
+    };
+    let bytes = match Format::from_path(path) {
+        Format::Cast => to_cast(&world.name, &frames, speed).into_bytes(),
+        Format::Gif => {
+            let pixels: Vec<Pixels> = frames.iter().map(snapshot::pixels).collect();
+            to_gif(&pixels, speed)?
+        }
+    };
+    fs::write(path, bytes)
+        .map_err(|e| format!("Couldn't write {}: {}", path.display(), e))
+}
+
+/// The frames as an asciicast, `speed` frames a second
+pub fn to_cast(title: &str, frames: &[GlyphCells], speed: u64) -> String {
+    let (height, width) = frames.first().map_or((0, 0), |frame| frame.dim());
+    let mut cast = format!(
+        "{{\"version\": 2, \"width\": {}, \"height\": {}, \"title\": {}}}\n",
+        width,
+        height,
+        json_string(title)
+    );
+    let speed = speed.max(1) as f64;
+    for (index, frame) in frames.iter().enumerate() {
+        // Home the cursor and draw over the last frame, a terminal wants \r\n
+        let text = format!("\x1b[H{}", snapshot::to_ansi(frame).replace('\n', "\r\n"));
+        cast.push_str(&format!(
+            "[{:.3}, \"o\", {}]\n",
+            index as f64 / speed,
+            json_string(&text)
+        ));
+    }
+    // An event with no output keeps the last frame up for a while
+    let end = frames.len().saturating_sub(1) as f64 / speed + HOLD;
+    cast.push_str(&format!("[{:.3}, \"o\", \"\"]\n", end));
+    cast
+}
+
+/// Quotes `text` as a JSON string
+pub fn json_string(text: &str) -> String {
+    let mut json = String::from("\"");
+    for ch in text.chars() {
+        match ch {
+            '"' => json.push_str("\\\""),
+            '\\' => json.push_str("\\\\"),
+            '\n' => json.push_str("\\n"),
+            '\r' => json.push_str("\\r"),
+            '\t' => json.push_str("\\t"),
+            ch if ch.is_control() => json.push_str(&format!("\\u{:04x}", ch as u32)),
+            ch => json.push(ch),
+        }
+    }
+    json.push('"');
+    json
+}
+
+/// The frames as a looping animated GIF, `speed` frames a second
+pub fn to_gif(frames: &[Pixels], speed: u64) -> Result<Vec<u8>, String> {
+    let (height, width) = frames.first().map_or((0, 0), |frame| frame.dim());
+    let (image_width, image_height) = (width * GIF_SCALE / 2, height * GIF_SCALE);
+
+    // Index 0 is left for the transparent pixels nothing was drawn on
+    let mut palette: Vec<(u8, u8, u8)> = vec![(0, 0, 0)];
+    let mut indexes = HashMap::new();
+    for pixel in frames.iter().flatten().flatten() {
+        if !indexes.contains_key(pixel) {
+            indexes.insert(*pixel, palette.len() as u8);
+            palette.push(*pixel);
+            if palette.len() > 256 {
+                return Err("Too many colours to fit in a GIF".to_string());
+            }
+        }
+    }
+    // The colour table holds a power of two colours, at least four
+    let bits = (palette.len().next_power_of_two().trailing_zeros() as u8).max(2);
+    palette.resize(1 << bits, (0, 0, 0));
+
+    let mut gif = b"GIF89a".to_vec();
+    gif.extend_from_slice(&(image_width as u16).to_le_bytes());
+    gif.extend_from_slice(&(image_height as u16).to_le_bytes());
+    // A global colour table of 2^bits entries, background colour 0, square pixels
+    gif.extend_from_slice(&[0x80 | (bits - 1), 0, 0]);
+    for (r, g, b) in &palette {
+        gif.extend_from_slice(&[*r, *g, *b]);
+    }
+    // Loop forever
+    gif.extend_from_slice(b"\x21\xff\x0bNETSCAPE2.0\x03\x01\x00\x00\x00");
+
+    let delay = (100 / speed.max(1)) as u16;
+    for (index, frame) in frames.iter().enumerate() {
+        let delay = if index + 1 == frames.len() {
+            delay + (HOLD * 100.0) as u16
+        } else {
+            delay
+        };
+        // Graphic control: keep the last frame under this one, colour 0 is see through
+        gif.extend_from_slice(&[0x21, 0xf9, 4, 0b0000_0101]);
+        gif.extend_from_slice(&delay.to_le_bytes());
+        gif.extend_from_slice(&[0, 0]);
+
+        gif.push(0x2c);
+        gif.extend_from_slice(&[0, 0, 0, 0]);
+        gif.extend_from_slice(&(image_width as u16).to_le_bytes());
+        gif.extend_from_slice(&(image_height as u16).to_le_bytes());
+        gif.push(0);
+
+        let mut image = Vec::with_capacity(image_width * image_height);
+        for row in frame.rows() {
+            let line: Vec<u8> = row
+                .iter()
+                .flat_map(|pixel| {
+                    let index = pixel.map_or(0, |pixel| indexes[&pixel]);
+                    std::iter::repeat_n(index, GIF_SCALE / 2)
+                })
+                .collect();
+            for _ in 0..GIF_SCALE {
+                image.extend_from_slice(&line);
+            }
+        }
+        gif.push(bits);
+        for block in lzw(&image, bits).chunks(255) {
+            gif.push(block.len() as u8);
+            gif.extend_from_slice(block);
+        }
+        gif.push(0);
+    }
+    gif.push(0x3b);
+    Ok(gif)
+}
+
+/// GIF flavoured LZW: variable width codes, least significant bit first, starting with
+/// a clear code and starting over whenever the 12 bit code table fills up
+pub fn lzw(data: &[u8], min_bits: u8) -> Vec<u8> {
+    let clear = 1u16 << min_bits;
+    let end = clear + 1;
+
+    let mut output = Vec::new();
+    let (mut buffer, mut buffered) = (0u32, 0u8);
+    let mut write = |code: u16, width: u8, output: &mut Vec<u8>| {
+        buffer |= u32::from(code) << buffered;
+        buffered += width;
+        while buffered >= 8 {
+            output.push(buffer as u8);
+            buffer >>= 8;
+            buffered -= 8;
+        }
+    };
+
+    let mut table: HashMap<(u16, u8), u16> = HashMap::new();
+    let mut next = end + 1;
+    let mut width = min_bits + 1;
+    write(clear, width, &mut output);
+
+    let mut bytes = data.iter();
+    let Some(&first) = bytes.next() else {
+        write(end, width, &mut output);
+        write(0, 7, &mut output);
+        return output;
+    };
+    let mut prefix = u16::from(first);
+    for &byte in bytes {
+        if let Some(&code) = table.get(&(prefix, byte)) {
+            prefix = code;
+            continue;
+        }
+        write(prefix, width, &mut output);
+        if next == 4096 {
+            write(clear, width, &mut output);
+            table.clear();
+            next = end + 1;
+            width = min_bits + 1;
+        } else {
+            table.insert((prefix, byte), next);
+            // The decoder widens its codes as soon as the next code needs it
+            if next == 1 << width && width < 12 {
+                width += 1;
+            }
+            next += 1;
+        }
+        prefix = u16::from(byte);
+    }
+    write(prefix, width, &mut output);
+    write(end, width, &mut output);
+    // Flush whatever's left of the last byte
+    write(0, 7, &mut output);
+    output
+}
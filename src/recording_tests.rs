@@ -0,0 +1,157 @@
+#[cfg(test)]
+mod tests {
+    use std::path::Path;
+
+    use crate::recording::*;
+    use crate::snapshot;
+    use crate::soko_state::splitmix64;
+    use crate::test_util::load_world;
+    use crate::types::{GlyphCells, Zoom};
+
+    const LEVEL: &str = "; Test
+#######
+#@ $ .#
+#######
+";
+
+    /// The board after each of `moves`, and before the first
+    fn frames(moves: &str) -> Vec<GlyphCells> {
+        frames_at(moves, Zoom::Far)
+    }
+
+    fn frames_at(moves: &str, zoom: Zoom) -> Vec<GlyphCells> {
+        let world = load_world(LEVEL);
+        crate::replay::load(&world, moves, crate::types::RunningState::Menu)
+            .unwrap()
+            .worlds
+            .iter()
+            .map(|world| snapshot::glyph_cells(world, zoom))
+            .collect()
+    }
+
+    /// Reads GIF LZW codes back, the way a GIF decoder does
+    fn unlzw(data: &[u8], min_bits: u8) -> Vec<u8> {
+        let clear = 1usize << min_bits;
+        let end = clear + 1;
+        let reset =
+            || -> Vec<Vec<u8>> { (0..=end).map(|code| vec![code as u8]).collect() };
+        let mut table = reset();
+        let mut width = min_bits + 1;
+        let mut prev: Option<usize> = None;
+        let mut output = Vec::new();
+        let mut bit = 0;
+        loop {
+            let code = (0..width as usize).fold(0, |code, i| {
+                let at = bit + i;
+                code | (((data[at / 8] >> (at % 8)) & 1) as usize) << i
+            });
+            bit += width as usize;
+            if code == clear {
+                table = reset();
+                width = min_bits + 1;
+                prev = None;
+                continue;
+            }
+            if code == end {
+                return output;
+            }
+            let entry = match (prev, code < table.len()) {
+                (None, _) => table[code].clone(),
+                (Some(_), true) => table[code].clone(),
+                (Some(prev), false) => {
+                    let mut entry = table[prev].clone();
+                    entry.push(table[prev][0]);
+                    entry
+                }
+            };
+            if let Some(prev) = prev {
+                let mut added = table[prev].clone();
+                added.push(entry[0]);
+                table.push(added);
+                if table.len() == 1 << width && width < 12 {
+                    width += 1;
+                }
+            }
+            output.extend_from_slice(&entry);
+            prev = Some(code);
+        }
+    }
+
+    #[test]
+    fn test_lzw_round_trips() {
+        let mut seed = 7;
+        // Few symbols makes long runs, many makes the table fill up and start over
+        for (symbols, bits) in [(2, 2), (5, 3), (200, 8)] {
+            let data: Vec<u8> = (0..20_000)
+                .map(|_| (splitmix64(&mut seed) % symbols) as u8)
+                .collect();
+            assert_eq!(unlzw(&lzw(&data, bits), bits), data, "{} symbols", symbols);
+        }
+        assert_eq!(unlzw(&lzw(&[], 2), 2), Vec::<u8>::new());
+        assert_eq!(unlzw(&lzw(&[3; 5000], 2), 2), vec![3; 5000]);
+    }
+
+    #[test]
+    fn test_json_strings_are_escaped() {
+        assert_eq!(json_string("a \"b\" \\ c"), r#""a \"b\" \\ c""#);
+        assert_eq!(json_string("\x1b[H\r\n"), r#""\u001b[H\r\n""#);
+        assert_eq!(json_string("▀"), "\"▀\"");
+    }
+
+    #[test]
+    fn test_cast_has_a_frame_per_move() {
+        let cast = to_cast("Test", &frames("rRR"), 4);
+        let lines: Vec<&str> = cast.lines().collect();
+
+        assert_eq!(
+            lines[0],
+            r#"{"version": 2, "width": 7, "height": 2, "title": "Test"}"#
+        );
+        // The start, three moves, then holding on the end
+        assert_eq!(lines.len(), 6);
+        assert!(lines[1].starts_with(r#"[0.000, "o", "\u001b[H"#));
+        assert!(lines[4].starts_with("[0.750, "));
+        assert_eq!(lines[5], r#"[2.750, "o", ""]"#);
+    }
+
+    #[test]
+    fn test_gif_frames() {
+        let pixels: Vec<_> = frames("rRR").iter().map(snapshot::pixels).collect();
+        let gif = to_gif(&pixels, 4).unwrap();
+
+        assert_eq!(&gif[..6], b"GIF89a");
+        assert_eq!(u16::from_le_bytes([gif[6], gif[7]]), 28);
+        assert_eq!(u16::from_le_bytes([gif[8], gif[9]]), 16);
+        assert_eq!(gif.last(), Some(&0x3b));
+        let images = gif.windows(4).filter(|w| w == &[0x21, 0xf9, 4, 5]).count();
+        assert_eq!(images, 4);
+    }
+
+    #[test]
+    fn test_player_walks_across_the_frames_when_close() {
+        let skin = Some((239, 204, 165));
+        let player: Vec<Vec<(usize, usize)>> = frames_at("rR", Zoom::Close)
+            .iter()
+            .map(|frame| {
+                snapshot::pixels(frame)
+                    .indexed_iter()
+                    .filter(|(_, &pixel)| pixel == skin)
+                    .map(|(at, _)| at)
+                    .collect()
+            })
+            .collect();
+
+        assert!(player.iter().all(|pixels| !pixels.is_empty()));
+        assert_ne!(player[0], player[1]);
+        assert_ne!(player[1], player[2]);
+
+        let cast = to_cast("Test", &frames_at("rR", Zoom::Close), 4);
+        assert!(cast.contains("2;239;204;165m"));
+    }
+
+    #[test]
+    fn test_gif_for_gif_files_only() {
+        assert_eq!(Format::from_path(Path::new("solve.GIF")), Format::Gif);
+        assert_eq!(Format::from_path(Path::new("solve.cast")), Format::Cast);
+    }
+}
@@ -1,7 +1,7 @@
-use crate::sprites::get_player_sprite_4;
+use crate::sprites::{get_player_sprite_4, get_player_sprite_4_walk};
 use crate::types::{
-    Coordinate, Entity, GameWindow, GlyphCell, GlyphCells, RenderGraph, RenderItem,
-    RenderNode, World, Zoom,
+    Coordinate, Entity, GameWindow, GlyphCell, GlyphCells, Motion, RenderGraph,
+    RenderItem, RenderNode, World, Zoom,
 };
 
 use ndarray::Array2;
@@ -34,13 +34,22 @@ fn render_pixels(
             }
             glyph_buffer
         }
-        RenderItem::Entity(entity) => {
-            let pos = entity.get_position();
-            draw_square(entity.color(), pos, pixel_size, glyph_buffer)
+        RenderItem::Entity(entity, motion) => {
+            let (x, y) = pixel_origin(&entity.get_position(), pixel_size, motion);
+            draw_pixels(entity.color(), x, y, pixel_size, glyph_buffer)
         }
     }
 }
 
+/// The top left pixel of an entity's square, shifted along by its motion
+fn pixel_origin(position: &Coordinate, size: usize, motion: &Motion) -> (i64, i64) {
+    let (dx, dy) = motion.offset;
+    (
+        (position.x * size) as i64 + (dx * size as f32).round() as i64,
+        (position.y * size) as i64 + (dy * size as f32).round() as i64,
+    )
+}
+
 fn draw_square(
     color: Color,
     position: Coordinate,
@@ -48,9 +57,23 @@ fn draw_square(
     glyph_buffer: GlyphCells,
 ) -> GlyphCells {
     let (x, y) = (position.x * size, position.y * size);
+    draw_pixels(color, x as i64, y as i64, size, glyph_buffer)
+}
+
+/// Draws a `size` by `size` square of pixels with its top left corner at pixel (`x`, `y`).
+/// Pixels that land off the buffer, including at negative positions, are skipped.
+fn draw_pixels(
+    color: Color,
+    x: i64,
+    y: i64,
+    size: usize,
+    glyph_buffer: GlyphCells,
+) -> GlyphCells {
     let mut glyph_buffer = glyph_buffer.clone();
-    for yi in y..(y + size) {
-        for xi in x..(x + size) {
+    let size = size as i64;
+    for yi in y.max(0)..(y + size).max(0) {
+        for xi in x.max(0)..(x + size).max(0) {
+            let (xi, yi) = (xi as usize, yi as usize);
             // Each "pixel" is actually a single unicode character so in transforming from a grid
             // of pixels to a grid of GlyphCells the y position in the Glyph Grid is half of the y
             // position in the pixel grid
@@ -83,18 +106,28 @@ fn render_sprites(item: &RenderItem, glyph_buffer: GlyphCells) -> GlyphCells {
             }
             glyph_buffer
         }
-        RenderItem::Entity(entity) => {
-            let pos = entity.get_position();
+        RenderItem::Entity(entity, motion) => {
+            let (x, y) = pixel_origin(&entity.get_position(), 4, motion);
             match entity {
                 Entity::Player(_) => {
                     let mut glyph_buffer = glyph_buffer.clone();
-                    let player_sprite = get_player_sprite_4();
+                    let player_sprite = match motion.walk_frame {
+                        Some(frame) => get_player_sprite_4_walk(frame),
+                        None => get_player_sprite_4(),
+                    };
+                    // Sprites are drawn a whole glyph at a time, so they can only move
+                    // vertically in steps of two pixels
+                    let (x, y) = (x, y.div_euclid(2));
                     for (yi, row) in player_sprite.chars.rows().into_iter().enumerate()
                     {
                         for (xi, pixel) in row.iter().enumerate() {
+                            let (gx, gy) = (x + xi as i64, y + yi as i64);
+                            if gx < 0 || gy < 0 {
+                                continue;
+                            }
                             let pos = Coordinate {
-                                y: yi + (pos.y * 2),
-                                x: xi + (pos.x * 4),
+                                y: gy as usize,
+                                x: gx as usize,
                             };
                             if !is_in_bounds(&pos, &glyph_buffer) {
                                 continue;
@@ -112,7 +145,7 @@ fn render_sprites(item: &RenderItem, glyph_buffer: GlyphCells) -> GlyphCells {
                     }
                     glyph_buffer
                 }
-                entity => draw_square(entity.color(), pos, 4, glyph_buffer),
+                entity => draw_pixels(entity.color(), x, y, 4, glyph_buffer),
             }
         }
     }
@@ -123,6 +156,8 @@ fn render_sprites(item: &RenderItem, glyph_buffer: GlyphCells) -> GlyphCells {
 /// # Parameters
 ///
 /// - `world`: A reference to a `World` struct, which contains the game's state, including the board and entities.
+/// - `motions`: How far each entity is from its tile while it slides between tiles, in the same order as
+///   `world.entities`.
 ///
 /// # Returns
 ///
@@ -147,18 +182,19 @@ fn render_sprites(item: &RenderItem, glyph_buffer: GlyphCells) -> GlyphCells {
 ///
 /// ```rust
 /// let world = World::new(); // Assume World::new initializes a game world
-/// let render_graph = generate_render_graph(&world);
+/// let render_graph = generate_render_graph(&world, vec![Motion::default(); world.entities.len()]);
 ///
 /// // `render_graph` can now be passed to a rendering function
 /// let area = Rect::new(0, 0, 80, 24); // Example rendering area
 /// let glyph_buffer = glypherize_graph(render_graph, area, render_fn);
 /// ```
-fn generate_render_graph(world: &World) -> RenderGraph {
+fn generate_render_graph(world: &World, motions: Vec<Motion>) -> RenderGraph {
     let children = world
         .entities
         .iter()
-        .map(|ent| RenderNode {
-            item: RenderItem::Entity(ent.clone()),
+        .zip(motions)
+        .map(|(ent, motion)| RenderNode {
+            item: RenderItem::Entity(ent.clone(), motion),
             children: None,
         })
         .collect();
@@ -244,6 +280,27 @@ where
     glypherize_node(&graph.root, glyph_buffer, &render_fn)
 }
 
+/// Draws `world` at `zoom` into a buffer the size of `area`, with each entity offset by
+/// its motion. This is everything the game window does short of copying the glyphs to
+/// the terminal, so it's also how boards get drawn into files.
+pub fn glyph_cells(
+    world: &World,
+    motions: Vec<Motion>,
+    zoom: Zoom,
+    area: Rect,
+) -> GlyphCells {
+    let graph = generate_render_graph(world, motions);
+    match zoom {
+        Zoom::Close => glypherize_graph(graph, area, render_sprites),
+        Zoom::Middle => {
+            glypherize_graph(graph, area, |item, buf| render_pixels(2, item, buf))
+        }
+        Zoom::Far => {
+            glypherize_graph(graph, area, |item, buf| render_pixels(1, item, buf))
+        }
+    }
+}
+
 /// Implements the `Widget` trait for the `GameWindow` struct, allowing it to be rendered within
 /// a specified area on the screen using a given buffer. The rendering is performed based on the
 /// zoom level of the game, with different rendering strategies applied depending on the selected zoom.
@@ -280,16 +337,7 @@ where
 impl Widget for GameWindow {
     #[allow(clippy::cast_precision_loss)]
     fn render(self, area: Rect, buf: &mut Buffer) {
-        let graph = generate_render_graph(&self.world);
-        let glyph_buffer = match self.zoom {
-            Zoom::Close => glypherize_graph(graph, area, render_sprites),
-            Zoom::Middle => {
-                glypherize_graph(graph, area, |item, buf| render_pixels(2, item, buf))
-            }
-            Zoom::Far => {
-                glypherize_graph(graph, area, |item, buf| render_pixels(1, item, buf))
-            }
-        };
+        let glyph_buffer = glyph_cells(&self.world, self.motions(), self.zoom, area);
 
         for (yi, row) in glyph_buffer.rows().into_iter().enumerate() {
             for (xi, cell) in row.iter().enumerate() {
@@ -0,0 +1,231 @@
+use std::io;
+use std::time::{Duration, Instant};
+
+use crate::board_layout::{self, PanelSize};
+use crate::keymap::{Action, Screen};
+use crate::lurd;
+use crate::soko_game::apply_move;
+use crate::types::{
+    GameWindow, Model, Replay, ReplayAction, RunningState, World, Zoom,
+};
+use ratatui::prelude::*;
+use ratatui::{
+    crossterm::event::{self, Event},
+    widgets::{Block, LineGauge, Paragraph},
+    Frame,
+};
+
+/// Playback speeds, in moves per second
+pub const SPEEDS: [u64; 6] = [1, 2, 4, 8, 16, 32];
+const DEFAULT_SPEED: usize = 2;
+
+/// The least room the side panel needs to be worth showing
+const PANEL: PanelSize = PanelSize {
+    width: 30,
+    height: 8,
+};
+
+/// Plays `solution` out on `world`, keeping every position along the way.
+///
+/// Fails on anything that isn't LURD, or on the first move that runs into a wall or a
+/// box it can't push. The stored moves are rewritten from what actually happened, so the
+/// push count is right even if the solution's capitalisation isn't.
+pub fn load(
+    world: &World,
+    solution: &str,
+    return_to: RunningState,
+) -> Result<Replay, String> {
+    let directions = lurd::parse(solution)?;
+
+    let mut worlds = vec![world.clone()];
+    let mut moves = String::new();
+    for (step, direction) in directions.into_iter().enumerate() {
+        let (next, outcome) = apply_move(&worlds[step], direction);
+        if outcome.is_blocked() {
+            return Err(format!("Move {} ({:?}) is blocked", step + 1, direction));
+        }
+        worlds.push(next);
+        moves.push(lurd::to_char(direction, outcome.is_push()));
+    }
+
+    Ok(Replay {
+        window: GameWindow::new(world.clone(), Zoom::Middle),
+        worlds,
+        moves,
+        position: 0,
+        playing: false,
+        speed: DEFAULT_SPEED,
+        last_step: Instant::now(),
+        return_to,
+        message: None,
+    })
+}
+
+pub fn view(model: &mut Model, frame: &mut Frame) {
+    let Some(replay) = &model.replay else {
+        return;
+    };
+
+    let [main_area, scrub_area] =
+        Layout::vertical([Constraint::Min(0), Constraint::Length(3)])
+            .areas(frame.area());
+    let world = &replay.window.world;
+    let Some(layout) = board_layout::split(main_area, world, replay.window.zoom, PANEL)
+    else {
+        board_layout::too_small(frame, world);
+        return;
+    };
+
+    let title = format!("REPLAY: {}", world.name);
+    let outer_left_block = Block::bordered().title(title);
+    let inner_left = outer_left_block.inner(layout.board);
+    frame.render_widget(outer_left_block, layout.board);
+    let mut board = replay.window.clone();
+    board.zoom = layout.zoom;
+    frame.render_widget(board, inner_left);
+
+    let played = &replay.moves[..replay.position];
+    let mut text = format!(
+        "
+    {}
+    Speed: {} moves/sec
+
+{}
+    Paste a LURD solution to watch it instead.
+",
+        if replay.playing { "PLAYING" } else { "PAUSED" },
+        SPEEDS[replay.speed],
+        model.keymap.help(Screen::Replay),
+    );
+    if let Some(message) = &replay.message {
+        text.push_str(&format!("\n    {}\n", message));
+    }
+    if let Some(panel_area) = layout.panel {
+        frame.render_widget(
+            Paragraph::new(text).block(Block::bordered().title("~REPLAY~")),
+            panel_area,
+        );
+    }
+
+    let total = replay.moves.len();
+    let ratio = if total == 0 {
+        0.0
+    } else {
+        replay.position as f64 / total as f64
+    };
+    let label = format!(
+        "Moves {}/{}  Pushes {}/{} ",
+        replay.position,
+        total,
+        lurd::push_count(played),
+        lurd::push_count(&replay.moves),
+    );
+    frame.render_widget(
+        LineGauge::default()
+            .block(Block::bordered())
+            .filled_style(Style::default().fg(Color::LightBlue))
+            .label(label)
+            .ratio(ratio),
+        scrub_area,
+    );
+}
+
+pub fn update(model: &mut Model, msg: ReplayAction) -> Option<ReplayAction> {
+    let Some(replay) = &mut model.replay else {
+        model.running_state = RunningState::Menu;
+        return None;
+    };
+    let last = replay.worlds.len() - 1;
+
+    match msg {
+        ReplayAction::Quit => {
+            model.running_state = replay.return_to;
+            model.replay = None;
+            return None;
+        }
+        ReplayAction::TogglePlay => {
+            // Playing from the end starts again from the beginning
+            if !replay.playing && replay.position == last {
+                replay.position = 0;
+            }
+            replay.playing = !replay.playing;
+            replay.last_step = Instant::now();
+        }
+        ReplayAction::Tick => {
+            if replay.position < last {
+                replay.position += 1;
+            }
+            replay.playing = replay.position < last;
+            replay.last_step = Instant::now();
+        }
+        ReplayAction::StepForward => {
+            replay.playing = false;
+            replay.position = (replay.position + 1).min(last);
+        }
+        ReplayAction::StepBack => {
+            replay.playing = false;
+            replay.position = replay.position.saturating_sub(1);
+        }
+        ReplayAction::First => replay.position = 0,
+        ReplayAction::Last => {
+            replay.playing = false;
+            replay.position = last;
+        }
+        ReplayAction::Faster => replay.speed = (replay.speed + 1).min(SPEEDS.len() - 1),
+        ReplayAction::Slower => replay.speed = replay.speed.saturating_sub(1),
+        ReplayAction::Paste(text) => {
+            match load(&replay.worlds[0], &text, replay.return_to) {
+                Ok(mut pasted) => {
+                    pasted.window.zoom = replay.window.zoom;
+                    pasted.speed = replay.speed;
+                    *replay = pasted;
+                }
+                Err(e) => replay.message = Some(format!("Couldn't load that: {}", e)),
+            }
+        }
+        ReplayAction::ZoomClose => replay.window.zoom = Zoom::Close,
+        ReplayAction::ZoomMiddle => replay.window.zoom = Zoom::Middle,
+        ReplayAction::ZoomFar => replay.window.zoom = Zoom::Far,
+    };
+    replay.window.world = replay.worlds[replay.position].clone();
+    None
+}
+
+pub fn handle_event(model: &Model) -> io::Result<Option<ReplayAction>> {
+    if event::poll(Duration::from_millis(20))? {
+        match event::read()? {
+            Event::Key(key) if key.kind == event::KeyEventKind::Press => {
+                return Ok(handle_key(model, key));
+            }
+            Event::Paste(text) => return Ok(Some(ReplayAction::Paste(text))),
+            _ => {}
+        }
+    }
+
+    if let Some(replay) = &model.replay {
+        let interval = Duration::from_millis(1000 / SPEEDS[replay.speed]);
+        if replay.playing && replay.last_step.elapsed() >= interval {
+            return Ok(Some(ReplayAction::Tick));
+        }
+    }
+    Ok(None)
+}
+
+pub fn handle_key(model: &Model, key: event::KeyEvent) -> Option<ReplayAction> {
+    match model.keymap.action(Screen::Replay, &key)? {
+        Action::Quit => Some(ReplayAction::Quit),
+        Action::Play => Some(ReplayAction::TogglePlay),
+        Action::Forward => Some(ReplayAction::StepForward),
+        Action::Back => Some(ReplayAction::StepBack),
+        Action::Faster => Some(ReplayAction::Faster),
+        Action::Slower => Some(ReplayAction::Slower),
+        Action::First => Some(ReplayAction::First),
+        Action::Last => Some(ReplayAction::Last),
+
+        // View
+        Action::ZoomFar => Some(ReplayAction::ZoomFar),
+        Action::ZoomMiddle => Some(ReplayAction::ZoomMiddle),
+        Action::ZoomClose => Some(ReplayAction::ZoomClose),
+        _ => None,
+    }
+}
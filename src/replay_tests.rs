@@ -0,0 +1,39 @@
+#[cfg(test)]
+mod tests {
+    use crate::replay::load;
+    use crate::test_util::load_world;
+    use crate::types::RunningState;
+
+    const LEVEL: &str = "; Corridor
+#######
+#@ $ .#
+#######
+";
+
+    #[test]
+    fn test_load_keeps_every_position() {
+        let replay = load(&load_world(LEVEL), "r r\nr", RunningState::Menu).unwrap();
+
+        assert_eq!(replay.worlds.len(), 4);
+        assert!(replay.worlds[3].is_sokoban_solved());
+        // The case is fixed up from what really happened
+        assert_eq!(replay.moves, "rRR");
+    }
+
+    #[test]
+    fn test_load_rejects_blocked_and_bad_moves() {
+        let world = load_world(LEVEL);
+        assert_eq!(
+            load(&world, "rRRR", RunningState::Menu).unwrap_err(),
+            "Move 4 (Right) is blocked"
+        );
+        assert!(load(&world, "rx", RunningState::Menu).is_err());
+    }
+
+    #[test]
+    fn test_empty_solution_loads_the_starting_position() {
+        let replay = load(&load_world(LEVEL), "", RunningState::Menu).unwrap();
+        assert_eq!(replay.worlds.len(), 1);
+        assert!(replay.moves.is_empty());
+    }
+}
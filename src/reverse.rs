@@ -0,0 +1,244 @@
+/*!
+ * Description:
+ *
+ * Reverse (pull) mode, for solving a level backwards.
+ *
+ * Playing in reverse every box starts on a goal and the player pulls them, one step at a
+ * time, back to the squares they occupy in the forward game. Until the first pull the
+ * player is free to jump over boxes, because the end of a forward solution can leave the
+ * player anywhere next to the last box pushed.
+ *
+ * Once the boxes are all back in place the pull sequence is run backwards (each pull
+ * becoming a push the other way) and prefixed with a walk from the forward player's
+ * position, which gives an ordinary forward LURD solution.
+ *
+ * Notes:
+ *
+ * While reversed, `Game::window`, `Game::history` and `Game::moves` describe the reverse
+ * game, so undo, reset and the move counter all keep working. The forward game is parked
+ * in `Game::reverse` until the mode is toggled off or the reverse solve completes.
+ */
+
+use crate::lurd;
+use crate::soko_game::{apply_move, get_new_position, player_path};
+use crate::types::{
+    Coordinate, Direction, Entity, Game, MoveOutcome, ReverseGame, SokoBox, Tile, World,
+};
+
+/// The starting position of a reverse game: every box on a goal and the player where
+/// it was. The player is last in the entity list so it is drawn over any box it jumps.
+pub fn reverse_world(world: &World) -> World {
+    let goals = world
+        .board
+        .indexed_iter()
+        .filter(|(_, tile)| matches!(tile, Tile::Goal))
+        .map(|((y, x), _)| Coordinate { x, y });
+    let box_count = world
+        .entities
+        .iter()
+        .filter(|ent| matches!(ent, Entity::SokoBox(_)))
+        .count();
+
+    let mut entities: Vec<Entity> = goals
+        .take(box_count)
+        .map(|position| Entity::SokoBox(SokoBox { position }))
+        .collect();
+    if let Some(player) = world
+        .entities
+        .iter()
+        .find(|e| matches!(e, Entity::Player(_)))
+    {
+        entities.push(player.clone());
+    }
+
+    World {
+        entities,
+        ..world.clone()
+    }
+}
+
+/// Moves the player one step in reverse mode. If a box is directly behind the player it
+/// follows into the square the player just left.
+///
+/// While `jumping` the player may also step onto boxes, but can't pull while standing on
+/// one.
+pub fn apply_pull(
+    world: &World,
+    direction: Direction,
+    jumping: bool,
+) -> (World, MoveOutcome) {
+    let mut level = world.clone();
+    let Some(player_index) = level
+        .entities
+        .iter()
+        .position(|entity| matches!(entity, Entity::Player(_)))
+    else {
+        return (level, MoveOutcome::BlockedByWall);
+    };
+
+    let from = level.entities[player_index].get_position();
+    let to = get_new_position(from.clone(), &direction);
+    if !matches!(
+        level.board.get(to.arr_index()),
+        Some(Tile::Floor | Tile::Goal | Tile::Empty)
+    ) {
+        return (level, MoveOutcome::BlockedByWall);
+    }
+    if !jumping && box_at(&level, &to).is_some() {
+        return (level, MoveOutcome::BlockedByBox);
+    }
+
+    let behind = get_new_position(from.clone(), &direction.opposite());
+    let pulled_box = if box_at(&level, &from).is_none() && behind != from {
+        box_at(&level, &behind)
+    } else {
+        None
+    };
+
+    if let Entity::Player(ref mut player) = level.entities[player_index] {
+        player.position = to.clone();
+    }
+    let Some((box_index, entity_index)) = pulled_box else {
+        return (level, MoveOutcome::Walked { from, to });
+    };
+    if let Entity::SokoBox(ref mut soko_box) = level.entities[entity_index] {
+        soko_box.position = from.clone();
+    }
+    (
+        level,
+        MoveOutcome::Pulled {
+            box_index,
+            from: behind,
+            to: from,
+        },
+    )
+}
+
+/// The box number and entity index of the box at `position`
+fn box_at(world: &World, position: &Coordinate) -> Option<(usize, usize)> {
+    world
+        .entities
+        .iter()
+        .enumerate()
+        .filter(|(_, entity)| matches!(entity, Entity::SokoBox(_)))
+        .enumerate()
+        .find(|(_, (_, entity))| entity.get_position() == *position)
+        .map(|(box_index, (entity_index, _))| (box_index, entity_index))
+}
+
+fn box_positions(world: &World) -> Vec<[usize; 2]> {
+    let mut positions: Vec<[usize; 2]> = world
+        .entities
+        .iter()
+        .filter(|ent| matches!(ent, Entity::SokoBox(_)))
+        .map(|ent| ent.get_position().arr_index())
+        .collect();
+    positions.sort();
+    positions
+}
+
+pub fn player_position(world: &World) -> Option<Coordinate> {
+    world
+        .entities
+        .iter()
+        .find(|ent| matches!(ent, Entity::Player(_)))
+        .map(|ent| ent.get_position())
+}
+
+/// Converts a finished reverse game into forward moves.
+///
+/// `target` is the forward world the pulls were aiming for, `reversed` the world at the
+/// end of the reverse game and `reverse_moves` the LURD moves played in reverse, jumps
+/// included. Returns `None` if the boxes aren't all back in place, or the forward player
+/// can't walk to where the reverse game finished.
+pub fn forward_solution(
+    target: &World,
+    reversed: &World,
+    reverse_moves: &str,
+) -> Option<String> {
+    if box_positions(target) != box_positions(reversed) {
+        return None;
+    }
+    // Everything before the first pull was the player picking a starting square
+    let first_pull = reverse_moves.find(|ch: char| ch.is_ascii_uppercase())?;
+
+    let walk = player_path(
+        target,
+        &player_position(target)?,
+        &player_position(reversed)?,
+    )?;
+    let mut forward: String = walk
+        .into_iter()
+        .map(|dir| lurd::to_char(dir, false))
+        .collect();
+    for ch in reverse_moves[first_pull..].chars().rev() {
+        let (direction, pulled) = lurd::from_char(ch)?;
+        forward.push(lurd::to_char(direction.opposite(), pulled));
+    }
+    Some(forward)
+}
+
+/// Swaps the game into reverse mode, or back out of it again
+pub fn toggle(game: &mut Game) {
+    match game.reverse.take() {
+        Some(forward) => {
+            game.window.world = forward.world;
+            game.history = forward.history;
+            game.moves = forward.moves;
+        }
+        None => {
+            let world = reverse_world(&game.window.world);
+            let forward = ReverseGame {
+                world: std::mem::replace(&mut game.window.world, world),
+                history: std::mem::take(&mut game.history),
+                moves: std::mem::take(&mut game.moves),
+            };
+            game.reverse = Some(forward);
+        }
+    }
+}
+
+/// Plays one move of a reverse game. When it brings every box back into place the game
+/// returns to forward mode with the converted solution played out, so the usual win
+/// handling takes over.
+pub fn update(game: &mut Game, direction: Direction) {
+    let jumping = !game.moves.chars().any(|ch| ch.is_ascii_uppercase());
+    let (new_level, outcome) = apply_pull(&game.window.world, direction, jumping);
+    if outcome.is_blocked() {
+        return;
+    }
+    game.history
+        .push(std::mem::replace(&mut game.window.world, new_level));
+    game.moves.push(lurd::to_char(direction, outcome.is_pull()));
+
+    let Some(forward) = &game.reverse else {
+        return;
+    };
+    let Some(solution) =
+        forward_solution(&forward.world, &game.window.world, &game.moves)
+    else {
+        return;
+    };
+
+    // Replay the solution in a scratch copy first, so a bad conversion leaves the
+    // reverse game intact.
+    let mut world = forward.world.clone();
+    let mut history = forward.history.clone();
+    let mut moves = forward.moves.clone();
+    for ch in solution.chars() {
+        let Some((direction, _)) = lurd::from_char(ch) else {
+            return;
+        };
+        let (next, outcome) = apply_move(&world, direction);
+        if outcome.is_blocked() {
+            return;
+        }
+        history.push(std::mem::replace(&mut world, next));
+        moves.push(lurd::to_char(direction, outcome.is_push()));
+    }
+
+    game.reverse = None;
+    game.window.world = world;
+    game.history = history;
+    game.moves = moves;
+}
@@ -0,0 +1,131 @@
+#[cfg(test)]
+mod tests {
+    use crate::reverse::{apply_pull, forward_solution, reverse_world, toggle, update};
+    use crate::soko_game::apply_move;
+    use crate::test_util::{load_world, new_game};
+    use crate::types::{Coordinate, Direction, MoveOutcome};
+
+    const LEVEL: &str = "; Corridor
+#######
+#@ $ .#
+#######
+";
+
+    #[test]
+    fn test_reverse_world_puts_boxes_on_goals() {
+        let reversed = reverse_world(&load_world(LEVEL));
+        assert!(reversed.is_sokoban_solved());
+    }
+
+    #[test]
+    fn test_pull_drags_the_box_behind_the_player() {
+        // Player standing right of the box on the goal
+        let world = load_world(
+            "; Pull
+#######
+#   *@#
+#######
+",
+        );
+        let (next, outcome) = apply_pull(&world, Direction::Left, false);
+        assert_eq!(outcome, MoveOutcome::BlockedByBox);
+
+        let (_, outcome) = apply_pull(&next, Direction::Right, false);
+        assert_eq!(outcome, MoveOutcome::BlockedByWall);
+
+        let world = load_world(
+            "; Pull
+#######
+#  *@ #
+#######
+",
+        );
+        let (_, outcome) = apply_pull(&world, Direction::Right, false);
+        assert_eq!(
+            outcome,
+            MoveOutcome::Pulled {
+                box_index: 0,
+                from: Coordinate { x: 3, y: 1 },
+                to: Coordinate { x: 4, y: 1 },
+            }
+        );
+    }
+
+    #[test]
+    fn test_jumping_lets_the_player_step_over_boxes() {
+        let reversed = reverse_world(&load_world(LEVEL));
+        let mut world = reversed;
+        for _ in 0..5 {
+            let (next, outcome) = apply_pull(&world, Direction::Right, true);
+            assert!(!outcome.is_pull());
+            world = next;
+        }
+        let (_, outcome) = apply_pull(&world, Direction::Right, true);
+        assert_eq!(outcome, MoveOutcome::BlockedByWall);
+    }
+
+    #[test]
+    fn test_forward_solution_inverts_the_pulls() {
+        let target = load_world(LEVEL);
+        let mut world = reverse_world(&target);
+        let mut moves = String::new();
+        // Jump over to the right of the box, then pull it back two squares and walk off
+        for (direction, jumping) in [
+            (Direction::Right, true),
+            (Direction::Right, true),
+            (Direction::Right, true),
+            (Direction::Right, true),
+            (Direction::Left, true),
+            (Direction::Left, false),
+            (Direction::Left, false),
+        ] {
+            let (next, outcome) = apply_pull(&world, direction, jumping);
+            assert!(!outcome.is_blocked());
+            moves.push(crate::lurd::to_char(direction, outcome.is_pull()));
+            world = next;
+        }
+
+        let solution = forward_solution(&target, &world, &moves).unwrap();
+        assert_eq!(solution, "rRR");
+
+        let mut forward = target;
+        for ch in solution.chars() {
+            let (direction, _) = crate::lurd::from_char(ch).unwrap();
+            forward = apply_move(&forward, direction).0;
+        }
+        assert!(forward.is_sokoban_solved());
+    }
+
+    #[test]
+    fn test_finishing_in_reverse_solves_the_forward_game() {
+        let mut game = new_game(load_world(LEVEL));
+        toggle(&mut game);
+        assert!(game.reverse.is_some());
+
+        for direction in [Direction::Right; 4] {
+            update(&mut game, direction);
+        }
+        update(&mut game, Direction::Left);
+        update(&mut game, Direction::Left);
+        assert!(game.reverse.is_some());
+        update(&mut game, Direction::Left);
+
+        assert!(game.reverse.is_none());
+        assert!(game.window.world.is_sokoban_solved());
+        assert_eq!(game.moves, "rRR");
+        assert_eq!(game.history.len(), 3);
+    }
+
+    #[test]
+    fn test_toggling_back_restores_the_forward_game() {
+        let mut game = new_game(load_world(LEVEL));
+        game.moves.push('r');
+        toggle(&mut game);
+        assert!(game.moves.is_empty());
+
+        toggle(&mut game);
+        assert!(game.reverse.is_none());
+        assert_eq!(game.moves, "r");
+        assert!(!game.window.world.is_sokoban_solved());
+    }
+}
@@ -0,0 +1,174 @@
+/*!
+ * Description:
+ *
+ * Reading save files written by any version of termban.
+ *
+ * Every save records the layout it was written with in `version`. Saves from before the
+ * field existed count as version 0. Loading parses the file as plain TOML, runs it
+ * through each migration between its version and `CURRENT_VERSION` in turn, and only
+ * then turns it into a `SaveFile`.
+ *
+ * Notes:
+ *
+ * When the layout of `SaveFile` (or anything in it) changes, bump `CURRENT_VERSION` and
+ * add a migration to the end of `MIGRATIONS` that rewrites the previous layout into the
+ * new one. Migrations work on the raw TOML table so they never depend on the current
+ * structs.
+ */
+
+use toml::{Table, Value};
+
+use crate::types::SaveFile;
+
+pub const CURRENT_VERSION: u32 = 4;
+
+/// Rewrites a save table from one version's layout into the next
+type Migration = fn(&mut Table) -> Result<(), String>;
+
+/// `MIGRATIONS[n]` takes a version `n` save to version `n + 1`
+const MIGRATIONS: [Migration; CURRENT_VERSION as usize] =
+    [v0_to_v1, v1_to_v2, v2_to_v3, v3_to_v4];
+
+/// Parses a save file, migrating it up to the current layout first
+pub fn parse_save_file(contents: &str) -> Result<SaveFile, String> {
+    let mut table: Table = contents.parse().map_err(|e| format!("{}", e))?;
+
+    let version = match table.get("version") {
+        None => 0,
+        Some(Value::Integer(version)) => u32::try_from(*version)
+            .map_err(|_| format!("{} is not a save version", version))?,
+        Some(_) => return Err("the save version is not a number".to_string()),
+    };
+    if version > CURRENT_VERSION {
+        return Err(format!(
+            "it was written by a newer version of termban (save version {})",
+            version
+        ));
+    }
+
+    for migration in &MIGRATIONS[version as usize..] {
+        migration(&mut table)?;
+    }
+    table.insert(
+        "version".to_string(),
+        Value::Integer(CURRENT_VERSION.into()),
+    );
+
+    let save_file: SaveFile = Value::Table(table)
+        .try_into()
+        .map_err(|e: toml::de::Error| e.message().to_string())?;
+    if save_file.saves.is_empty() {
+        return Err("it has no profiles in it".to_string());
+    }
+    Ok(save_file)
+}
+
+/// Version 0 covers everything before saves were versioned: a bare list of saves, later
+/// joined by stored solutions, the active profile and options, all optional. Version 1
+/// writes them all out.
+fn v0_to_v1(table: &mut Table) -> Result<(), String> {
+    for save in saves_mut(table)? {
+        save.entry("solutions")
+            .or_insert_with(|| Value::Table(Table::new()));
+    }
+
+    table.entry("active").or_insert(Value::Integer(0));
+    table
+        .entry("options")
+        .or_insert_with(|| Value::Table(Table::new()));
+    Ok(())
+}
+
+/// Version 2 keeps the moves of unfinished levels
+fn v1_to_v2(table: &mut Table) -> Result<(), String> {
+    for save in saves_mut(table)? {
+        save.entry("in_progress")
+            .or_insert_with(|| Value::Table(Table::new()));
+    }
+    Ok(())
+}
+
+/// The only collection the game could play before version 3
+const LEGACY_COLLECTION: &str = "micro2";
+
+/// Version 3 keeps progress per collection and per level, rather than one level index
+/// and solutions keyed by position. Without the level file to hand the old progress is
+/// filed by position under `by_index`, and matched up with the levels when the game
+/// next opens the collection.
+fn v2_to_v3(table: &mut Table) -> Result<(), String> {
+    for save in saves_mut(table)? {
+        let mut by_index = Table::new();
+        // Reaching a level meant solving every one before it
+        let reached = match save.remove("level") {
+            Some(Value::Integer(reached)) => reached.max(0),
+            _ => return Err("one of the saves has no level".to_string()),
+        };
+        for index in 0..reached {
+            sub_table(&mut by_index, index.to_string())
+                .insert("solved".to_string(), Value::Boolean(true));
+        }
+        if let Some(Value::Table(solutions)) = save.remove("solutions") {
+            for (index, solution) in solutions {
+                let level = sub_table(&mut by_index, index);
+                level.insert("solved".to_string(), Value::Boolean(true));
+                level.insert("solution".to_string(), solution);
+            }
+        }
+        if let Some(Value::Table(in_progress)) = save.remove("in_progress") {
+            for (index, moves) in in_progress {
+                sub_table(&mut by_index, index)
+                    .insert("in_progress".to_string(), moves);
+            }
+        }
+
+        let mut progress = Table::new();
+        progress.insert("by_index".to_string(), Value::Table(by_index));
+        let mut collections = Table::new();
+        collections.insert(LEGACY_COLLECTION.to_string(), Value::Table(progress));
+        save.insert("collections".to_string(), Value::Table(collections));
+    }
+    Ok(())
+}
+
+/// Version 4 can mark levels skipped, keep the fewest moves each level takes, set unlock
+/// rules per collection and remember the collection last played. Until one is set none
+/// of them are written. It also stops keeping each collection's content hash, since a
+/// collection is known by its name alone.
+fn v3_to_v4(table: &mut Table) -> Result<(), String> {
+    for save in saves_mut(table)? {
+        if let Some(Value::Table(collections)) = save.get_mut("collections") {
+            for progress in collections
+                .iter_mut()
+                .filter_map(|(_, progress)| progress.as_table_mut())
+            {
+                progress.remove("hash");
+            }
+        }
+    }
+    Ok(())
+}
+
+/// The table under `key` in `table`, added if it isn't there yet
+fn sub_table(table: &mut Table, key: String) -> &mut Table {
+    let entry = table
+        .entry(key)
+        .or_insert_with(|| Value::Table(Table::new()));
+    if !entry.is_table() {
+        *entry = Value::Table(Table::new());
+    }
+    entry.as_table_mut().expect("it was just made a table")
+}
+
+/// The profiles in a save table
+fn saves_mut(table: &mut Table) -> Result<Vec<&mut Table>, String> {
+    let Some(Value::Array(saves)) = table.get_mut("saves") else {
+        return Err("it has no list of saves".to_string());
+    };
+    saves
+        .iter_mut()
+        .map(|save| match save {
+            Value::Table(save) => Ok(save),
+            _ => Err("one of the saves is not a table".to_string()),
+        })
+        .collect()
+}
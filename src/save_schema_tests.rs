@@ -0,0 +1,168 @@
+#[cfg(test)]
+mod tests {
+    use crate::save_schema::*;
+    use crate::types::{Collection, Optimal, SaveFile, Unlock};
+
+    /// Stands in for the collection every save used to be for
+    fn micro2(levels: usize) -> Collection {
+        Collection {
+            name: "micro2".to_string(),
+            hash: "0123456789abcdef".to_string(),
+            level_ids: (0..levels)
+                .map(|index| format!("level-{}", index))
+                .collect(),
+            ..Collection::default()
+        }
+    }
+
+    #[test]
+    fn test_unversioned_save_is_migrated() {
+        // The layout from before saves had versions, profiles or solutions
+        let mut save_file = parse_save_file(
+            r#"
+            [[saves]]
+            name = "My Save"
+            level = 4
+            "#,
+        )
+        .unwrap();
+        save_file.adopt_collection(&micro2(10));
+
+        assert_eq!(save_file.version, CURRENT_VERSION);
+        assert_eq!(save_file.current().first_unfinished(&micro2(10)), 4);
+        assert!(save_file
+            .current()
+            .level(&micro2(10), 0)
+            .unwrap()
+            .solution
+            .is_none());
+        assert_eq!(save_file.active, 0);
+        assert!(save_file.options.animations);
+    }
+
+    #[test]
+    fn test_version_2_progress_moves_to_level_ids() {
+        let mut save_file = parse_save_file(
+            r#"
+            version = 2
+            active = 0
+
+            [options]
+            animations = true
+
+            [[saves]]
+            name = "My Save"
+            level = 2
+
+            [saves.solutions]
+            0 = "rrU"
+            5 = "lD"
+
+            [saves.in_progress]
+            2 = "ul"
+            "#,
+        )
+        .unwrap();
+        assert_eq!(save_file.version, CURRENT_VERSION);
+
+        let collection = micro2(4);
+        save_file.adopt_collection(&collection);
+        let save = save_file.current();
+        assert!(save.is_solved(&collection, 0));
+        assert!(save.is_solved(&collection, 1));
+        assert!(!save.is_solved(&collection, 2));
+        let first = save.level(&collection, 0).unwrap();
+        assert_eq!(first.solution.as_deref(), Some("rrU"));
+        let third = save.level(&collection, 2).unwrap();
+        assert_eq!(third.in_progress.as_deref(), Some("ul"));
+
+        // Level 6 isn't in the collection any more, so its solution is dropped
+        let progress = &save.collections["micro2"];
+        assert_eq!(progress.levels.len(), 3);
+        assert!(progress.by_index.is_empty());
+        assert_eq!(progress.levels["level-0"].solution.as_deref(), Some("rrU"));
+    }
+
+    #[test]
+    fn test_version_3_save_loads_unchanged() {
+        let save_file = parse_save_file(
+            r#"
+            version = 3
+            active = 0
+
+            [options]
+            animations = true
+
+            [[saves]]
+            name = "My Save"
+
+            [saves.collections.micro2]
+            hash = "0123456789abcdef"
+
+            [saves.collections.micro2.levels.level-0]
+            solved = true
+            solution = "rrU"
+            "#,
+        )
+        .unwrap();
+        assert_eq!(save_file.version, CURRENT_VERSION);
+        assert!(save_file.collection.is_empty());
+        assert!(save_file.options.unlock.is_empty());
+        let level = &save_file.current().collections["micro2"].levels["level-0"];
+        assert!(level.solved && !level.skipped);
+        assert_eq!(level.solution.as_deref(), Some("rrU"));
+    }
+
+    #[test]
+    fn test_current_save_round_trips() {
+        let collection = micro2(3);
+        let mut save_file = SaveFile::new();
+        let save = save_file.current_mut();
+        save.level_mut(&collection, 0).solved = true;
+        save.level_mut(&collection, 0).solution = Some("rrU".to_string());
+        save.level_mut(&collection, 2).in_progress = Some("ul".to_string());
+        save.level_mut(&collection, 0).optimal = Some(Optimal::Moves(3));
+        save.level_mut(&collection, 1).optimal = Some(Optimal::Unknown);
+        save_file.options.animations = false;
+        save_file
+            .options
+            .unlock
+            .insert("micro2".to_string(), Unlock::Window(3));
+        save_file
+            .options
+            .unlock
+            .insert("micro".to_string(), Unlock::Open);
+
+        let contents = toml::to_string(&save_file).unwrap();
+        let loaded = parse_save_file(&contents).unwrap();
+        let save = loaded.current();
+        assert_eq!(save.first_unfinished(&collection), 1);
+        let first = save.level(&collection, 0).unwrap();
+        assert_eq!(first.solution.as_deref(), Some("rrU"));
+        let third = save.level(&collection, 2).unwrap();
+        assert_eq!(third.in_progress.as_deref(), Some("ul"));
+        assert_eq!(first.optimal, Some(Optimal::Moves(3)));
+        let second = save.level(&collection, 1).unwrap();
+        assert_eq!(second.optimal, Some(Optimal::Unknown));
+        assert!(!loaded.options.animations);
+        assert_eq!(loaded.options.unlock_for(&collection), Unlock::Window(3));
+    }
+
+    #[test]
+    fn test_unreadable_saves_are_errors() {
+        assert!(parse_save_file("this is not toml").is_err());
+        assert!(parse_save_file("version = 1").is_err());
+        assert!(parse_save_file("saves = []\nversion = 1").is_err());
+        assert!(parse_save_file("[[saves]]\nname = \"No Level\"").is_err());
+    }
+
+    #[test]
+    fn test_newer_saves_are_left_alone() {
+        let contents = format!(
+            "version = {}\n[[saves]]\nname = \"Future\"\nlevel = 0",
+            CURRENT_VERSION + 1
+        );
+        let error = parse_save_file(&contents).unwrap_err();
+        assert!(error.contains("newer version"));
+    }
+}
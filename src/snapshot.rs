@@ -0,0 +1,333 @@
+/*!
+ * Description:
+ *
+ * Draws a level, or a position part way through a solution, into a file: a PNG or SVG
+ * image, or text with ANSI colour codes that looks like the game when printed to a
+ * terminal. Handy for documentation and bug reports.
+ *
+ * The board goes through the same `RenderGraph` and `GlyphCells` as the game window, so
+ * the picture matches what's on screen at that zoom, palette and all.
+ *
+ * Usage:
+ *
+ *   termban render levels.ban --level 3 --moves rrU --output level3.png
+ *
+ * Notes:
+ *
+ * A glyph is split into four pixels, two across and two down, each taking the
+ * foreground colour if the glyph covers at least half of it and the background colour
+ * if not. The board's `▀` half blocks come out exactly, and so do the quadrant blocks
+ * the player sprite is drawn with when close. Shades and eighth blocks are rounded to
+ * the nearest quarter, and any other character is drawn as its background. The ANSI
+ * text is drawn back from those pixels with the matching block glyphs. Pixels nothing
+ * was drawn on are left transparent.
+ *
+ * A glyph is twice as tall as it is wide, so the pixels are too: images draw each
+ * column half as wide as each row is tall.
+ *
+ * The PNG is written without compression (stored deflate blocks) so no image library
+ * is needed. Boards are small, so the files still are.
+ */
+
+use std::fs;
+use std::path::Path;
+
+use ndarray::Array2;
+use ratatui::layout::Rect;
+use ratatui::style::Color;
+
+use crate::types::{GlyphCell, GlyphCells, Motion, RunningState, World, Zoom};
+use crate::{board_layout, collection, render, replay};
+
+/// How many image pixels tall each pixel is drawn
+const SCALE: usize = 8;
+
+/// And how many across, half as many since glyphs are twice as tall as they are wide
+const SCALE_ACROSS: usize = SCALE / 2;
+
+/// The block glyph for each set of quarters, bit 0 the top left, 1 the top right, 2
+/// the bottom left and 3 the bottom right
+const QUADRANTS: [char; 16] = [
+    ' ', '▘', '▝', '▀', '▖', '▌', '▞', '▛', '▗', '▚', '▐', '▜', '▄', '▙', '▟', '█',
+];
+
+type Rgb = (u8, u8, u8);
+
+/// The picture as a grid of pixels, `None` where nothing was drawn
+pub type Pixels = Array2<Option<Rgb>>;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Format {
+    Png,
+    Svg,
+    Ansi,
+}
+
+impl Format {
+    /// Picks the format from a file's extension, anything unknown gets ANSI text
+    pub fn from_path(path: &Path) -> Format {
+        let extension = path
+            .extension()
+            .map(|ext| ext.to_string_lossy().to_lowercase());
+        match extension.as_deref() {
+            Some("png") => Format::Png,
+            Some("svg") => Format::Svg,
+            _ => Format::Ansi,
+        }
+    }
+}
+
+/// Runs `termban render`: draws level `level` (1 based) of `file` after playing
+/// `moves`, into `output` or as ANSI text on stdout
+pub fn run(
+    file: &Path,
+    level: usize,
+    moves: &str,
+    zoom: Zoom,
+    output: Option<&Path>,
+) -> Result<(), String> {
+    let (_, worlds) = collection::load(file)?;
+    let world = worlds.get(level - 1).ok_or_else(|| {
+        format!("{} only has {} levels", file.display(), worlds.len())
+    })?;
+    let world = if moves.is_empty() {
+        world.clone()
+    } else {
+        let mut replay = replay::load(world, moves, RunningState::Menu)?;
+        replay.worlds.pop().unwrap_or_else(|| world.clone())
+    };
+
+    let cells = glyph_cells(&world, zoom);
+    let Some(path) = output else {
+        print!("{}", to_ansi(&cells));
+        return Ok(());
+    };
+    let bytes = match Format::from_path(path) {
+        Format::Png => to_png(&pixels(&cells)),
+        Format::Svg => to_svg(&pixels(&cells)).into_bytes(),
+        Format::Ansi => to_ansi(&cells).into_bytes(),
+    };
+    fs::write(path, bytes)
+        .map_err(|e| format!("Couldn't write {}: {}", path.display(), e))
+}
+
+/// The board drawn at `zoom`, exactly big enough to hold it
+pub fn glyph_cells(world: &World, zoom: Zoom) -> GlyphCells {
+    let (width, height) = board_layout::board_size(world, zoom);
+    let motions = vec![Motion::default(); world.entities.len()];
+    render::glyph_cells(world, motions, zoom, Rect::new(0, 0, width, height))
+}
+
+/// Splits each glyph into its four pixels
+pub fn pixels(cells: &GlyphCells) -> Pixels {
+    let (height, width) = cells.dim();
+    Array2::from_shape_fn((height * 2, width * 2), |(y, x)| {
+        quarter_colors(&cells[[y / 2, x / 2]])[(y % 2) * 2 + x % 2]
+    })
+}
+
+pub fn to_ansi(cells: &GlyphCells) -> String {
+    let mut text = String::new();
+    for row in cells.rows() {
+        for cell in row {
+            let quarters = quarter_colors(cell);
+            text.push_str("\x1b[0m");
+            // Draw the top left's colour in the foreground, or the first colour there
+            // is when that's transparent
+            let Some(fg) = quarters.iter().flatten().next().copied() else {
+                text.push(' ');
+                continue;
+            };
+            let mask = quarters
+                .iter()
+                .enumerate()
+                .filter(|(_, &quarter)| quarter == Some(fg))
+                .fold(0, |mask, (i, _)| mask | 1 << i);
+            text.push_str(&ansi(38, fg));
+            let bg = quarters.iter().flatten().find(|&&color| color != fg);
+            if let Some(&bg) = bg {
+                text.push_str(&ansi(48, bg));
+            }
+            text.push(QUADRANTS[mask]);
+        }
+        text.push_str("\x1b[0m\n");
+    }
+    text
+}
+
+/// The colours of a glyph's top left, top right, bottom left and bottom right
+fn quarter_colors(cell: &GlyphCell) -> [Option<Rgb>; 4] {
+    let mask = quarters(cell.glyph);
+    std::array::from_fn(|i| {
+        let color = if mask & 1 << i != 0 { cell.fg } else { cell.bg };
+        color.map(rgb)
+    })
+}
+
+/// Which quarters of `glyph` are in the foreground colour, as in `QUADRANTS`
+fn quarters(glyph: char) -> usize {
+    if let Some(mask) = QUADRANTS.iter().position(|&quadrant| quadrant == glyph) {
+        return mask;
+    }
+    match glyph {
+        // Blocks rising from the bottom, a quarter is filled from half its height
+        '▂' | '▃' | '▅' => 0b1100,
+        '▆' | '▇' => 0b1111,
+        // Blocks from the left, likewise from half its width
+        '▎' | '▍' | '▋' => 0b0101,
+        '▊' | '▉' => 0b1111,
+        '▓' => 0b1111,
+        _ => 0,
+    }
+}
+
+/// A 24 bit colour code, 38 for the foreground or 48 for the background
+fn ansi(code: u8, (r, g, b): Rgb) -> String {
+    format!("\x1b[{};2;{};{};{}m", code, r, g, b)
+}
+
+pub fn to_svg(pixels: &Pixels) -> String {
+    let (height, width) = pixels.dim();
+    let mut svg = format!(
+        "<svg xmlns=\"http://www.w3.org/2000/svg\" width=\"{w}\" height=\"{h}\" \
+         viewBox=\"0 0 {w} {h}\" shape-rendering=\"crispEdges\">\n",
+        w = width * SCALE_ACROSS,
+        h = height * SCALE
+    );
+    for (y, row) in pixels.rows().into_iter().enumerate() {
+        // Runs of the same colour along a row go in one rect
+        let mut x = 0;
+        while x < width {
+            let color = row[x];
+            let run = row
+                .iter()
+                .skip(x)
+                .take_while(|&&pixel| pixel == color)
+                .count();
+            if let Some((r, g, b)) = color {
+                svg.push_str(&format!(
+                    "  <rect x=\"{}\" y=\"{}\" width=\"{}\" height=\"{}\" \
+                     fill=\"#{:02x}{:02x}{:02x}\"/>\n",
+                    x * SCALE_ACROSS,
+                    y * SCALE,
+                    run * SCALE_ACROSS,
+                    SCALE,
+                    r,
+                    g,
+                    b
+                ));
+            }
+            x += run;
+        }
+    }
+    svg.push_str("</svg>\n");
+    svg
+}
+
+pub fn to_png(pixels: &Pixels) -> Vec<u8> {
+    let (height, width) = pixels.dim();
+    let (image_width, image_height) = (width * SCALE_ACROSS, height * SCALE);
+
+    // Each row of the image starts with a filter type byte, 0 for none
+    let mut raw = Vec::with_capacity((image_width * 4 + 1) * image_height);
+    for row in pixels.rows() {
+        let mut line = vec![0];
+        for pixel in row {
+            let rgba = match pixel {
+                Some((r, g, b)) => [*r, *g, *b, 255],
+                None => [0, 0, 0, 0],
+            };
+            for _ in 0..SCALE_ACROSS {
+                line.extend_from_slice(&rgba);
+            }
+        }
+        for _ in 0..SCALE {
+            raw.extend_from_slice(&line);
+        }
+    }
+
+    let mut header = Vec::new();
+    header.extend_from_slice(&(image_width as u32).to_be_bytes());
+    header.extend_from_slice(&(image_height as u32).to_be_bytes());
+    // 8 bits per channel, RGBA, then the standard compression, filter and interlace
+    header.extend_from_slice(&[8, 6, 0, 0, 0]);
+
+    let mut png = b"\x89PNG\r\n\x1a\n".to_vec();
+    png_chunk(&mut png, b"IHDR", &header);
+    png_chunk(&mut png, b"IDAT", &zlib_stored(&raw));
+    png_chunk(&mut png, b"IEND", &[]);
+    png
+}
+
+fn png_chunk(png: &mut Vec<u8>, kind: &[u8; 4], data: &[u8]) {
+    png.extend_from_slice(&(data.len() as u32).to_be_bytes());
+    let start = png.len();
+    png.extend_from_slice(kind);
+    png.extend_from_slice(data);
+    let crc = crc32(&png[start..]);
+    png.extend_from_slice(&crc.to_be_bytes());
+}
+
+/// Wraps `data` in a zlib stream without compressing it
+fn zlib_stored(data: &[u8]) -> Vec<u8> {
+    // Deflate with a 32K window, no preset dictionary
+    let mut zlib = vec![0x78, 0x01];
+    let mut blocks = data.chunks(u16::MAX as usize).peekable();
+    if blocks.peek().is_none() {
+        zlib.extend_from_slice(&[1, 0, 0, 0xff, 0xff]);
+    }
+    while let Some(block) = blocks.next() {
+        let last = blocks.peek().is_none();
+        let len = block.len() as u16;
+        zlib.push(u8::from(last));
+        zlib.extend_from_slice(&len.to_le_bytes());
+        zlib.extend_from_slice(&(!len).to_le_bytes());
+        zlib.extend_from_slice(block);
+    }
+    zlib.extend_from_slice(&adler32(data).to_be_bytes());
+    zlib
+}
+
+pub fn crc32(bytes: &[u8]) -> u32 {
+    !bytes.iter().fold(!0u32, |crc, &byte| {
+        (0..8).fold(crc ^ u32::from(byte), |crc, _| {
+            if crc & 1 == 1 {
+                (crc >> 1) ^ 0xedb8_8320
+            } else {
+                crc >> 1
+            }
+        })
+    })
+}
+
+pub fn adler32(bytes: &[u8]) -> u32 {
+    let (a, b) = bytes.iter().fold((1u32, 0u32), |(a, b), &byte| {
+        let a = (a + u32::from(byte)) % 65521;
+        (a, (b + a) % 65521)
+    });
+    (b << 16) | a
+}
+
+/// The red, green and blue of a terminal colour. The game's palette is all RGB, the
+/// named colours get the usual xterm values.
+fn rgb(color: Color) -> Rgb {
+    match color {
+        Color::Rgb(r, g, b) => (r, g, b),
+        Color::Black => (0, 0, 0),
+        Color::Red => (205, 0, 0),
+        Color::Green => (0, 205, 0),
+        Color::Yellow => (205, 205, 0),
+        Color::Blue => (0, 0, 238),
+        Color::Magenta => (205, 0, 205),
+        Color::Cyan => (0, 205, 205),
+        Color::Gray => (229, 229, 229),
+        Color::DarkGray => (127, 127, 127),
+        Color::LightRed => (255, 0, 0),
+        Color::LightGreen => (0, 255, 0),
+        Color::LightYellow => (255, 255, 0),
+        Color::LightBlue => (92, 92, 255),
+        Color::LightMagenta => (255, 0, 255),
+        Color::LightCyan => (0, 255, 255),
+        _ => (255, 255, 255),
+    }
+}
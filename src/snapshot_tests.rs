@@ -0,0 +1,117 @@
+#[cfg(test)]
+mod tests {
+    use std::path::Path;
+
+    use ndarray::Array2;
+    use ratatui::style::Color;
+
+    use crate::snapshot::*;
+    use crate::test_util::load_world;
+    use crate::types::{GlyphCell, Zoom};
+
+    const LEVEL: &str = "; Test
+#####
+#@$.#
+#####
+";
+
+    #[test]
+    fn test_format_comes_from_the_extension() {
+        assert_eq!(Format::from_path(Path::new("level.png")), Format::Png);
+        assert_eq!(Format::from_path(Path::new("level.SVG")), Format::Svg);
+        assert_eq!(Format::from_path(Path::new("level.ans")), Format::Ansi);
+        assert_eq!(Format::from_path(Path::new("level")), Format::Ansi);
+    }
+
+    #[test]
+    fn test_one_pixel_per_tile_when_far() {
+        let cells = glyph_cells(&load_world(LEVEL), Zoom::Far);
+        // 3 rows of tiles fit in 2 rows of glyphs
+        assert_eq!(cells.dim(), (2, 5));
+
+        // Two pixels across each glyph, each half as wide as it is tall
+        let pixels = pixels(&cells);
+        assert_eq!(pixels.dim(), (4, 10));
+        // The last pixel row is past the bottom of the board
+        assert!(pixels.row(3).iter().all(|pixel| pixel.is_none()));
+        assert!(pixels[[0, 0]].is_some());
+        assert_eq!(pixels[[1, 2]], pixels[[1, 3]]);
+        assert_ne!(pixels[[1, 2]], pixels[[1, 4]], "player and box differ");
+    }
+
+    #[test]
+    fn test_player_sprite_is_drawn_when_close() {
+        let cells = glyph_cells(&load_world(LEVEL), Zoom::Close);
+        let pixels = pixels(&cells);
+        let skin = Some((239, 204, 165));
+        let hair = Some((52, 32, 33));
+        assert!(pixels.iter().any(|&pixel| pixel == skin));
+        assert!(pixels.iter().any(|&pixel| pixel == hair));
+
+        // The sprite's quadrant blocks come out as quadrant blocks
+        let text = to_ansi(&cells);
+        assert!(text.contains("38;2;239;204;165") || text.contains("48;2;239;204;165"));
+        assert!(text.contains('▚') || text.contains('▞'));
+    }
+
+    #[test]
+    fn test_quarters_of_block_glyphs() {
+        let colors = |glyph| {
+            let cell = GlyphCell {
+                glyph,
+                fg: Some(Color::Rgb(1, 1, 1)),
+                bg: None,
+            };
+            pixels(&Array2::from_elem((1, 1), cell))
+                .iter()
+                .map(Option::is_some)
+                .collect::<Vec<_>>()
+        };
+        assert_eq!(colors('▀'), [true, true, false, false]);
+        assert_eq!(colors('▙'), [true, false, true, true]);
+        assert_eq!(colors('▞'), [false, true, true, false]);
+        assert_eq!(colors('▂'), [false, false, true, true]);
+        assert_eq!(colors('@'), [false, false, false, false]);
+    }
+
+    #[test]
+    fn test_ansi_has_a_line_per_glyph_row() {
+        let text = to_ansi(&glyph_cells(&load_world(LEVEL), Zoom::Middle));
+        assert_eq!(text.lines().count(), 3);
+        assert!(text.contains("\x1b[38;2;"));
+        assert!(text.ends_with("\x1b[0m\n"));
+    }
+
+    #[test]
+    fn test_svg_merges_runs_of_a_colour() {
+        let pixels = pixels(&glyph_cells(&load_world(LEVEL), Zoom::Far));
+        let svg = to_svg(&pixels);
+        assert!(svg.starts_with("<svg "));
+        assert!(svg.contains("width=\"40\" height=\"32\""));
+        // The top wall is one rect, not five
+        assert!(svg.contains("<rect x=\"0\" y=\"0\" width=\"40\" height=\"8\""));
+    }
+
+    #[test]
+    fn test_png_is_well_formed() {
+        let pixels = pixels(&glyph_cells(&load_world(LEVEL), Zoom::Far));
+        let png = to_png(&pixels);
+
+        assert_eq!(&png[..8], b"\x89PNG\r\n\x1a\n");
+        assert_eq!(&png[12..16], b"IHDR");
+        assert_eq!(u32::from_be_bytes(png[16..20].try_into().unwrap()), 40);
+        assert_eq!(u32::from_be_bytes(png[20..24].try_into().unwrap()), 32);
+        assert_eq!(&png[png.len() - 8..png.len() - 4], b"IEND");
+
+        // Every chunk's checksum covers its type and data
+        let length = u32::from_be_bytes(png[8..12].try_into().unwrap()) as usize;
+        let crc = u32::from_be_bytes(png[16 + length..20 + length].try_into().unwrap());
+        assert_eq!(crc, crc32(&png[12..16 + length]));
+    }
+
+    #[test]
+    fn test_checksums() {
+        assert_eq!(crc32(b"123456789"), 0xcbf4_3926);
+        assert_eq!(adler32(b"Wikipedia"), 0x11e6_0398);
+    }
+}
@@ -1,70 +1,148 @@
+use std::collections::VecDeque;
 use std::io;
 use std::time::Duration;
 
+use crate::board_layout::{self, PanelSize};
+use crate::keymap::{Action, Screen};
+use crate::soko_loader::to_xsb;
+use crate::transform::{self, Symmetry};
 use crate::types::{
-    Coordinate, Direction, Entity, GameAction, Model, RunningState, Tile, World, Zoom,
+    CollectionFile, Coordinate, Direction, Entity, Game, GameAction, Model,
+    MoveOutcome, RunningState, Tile, World, Zoom,
 };
+use crate::{clipboard, collection, copy_text, lurd, reverse};
+use ndarray::Array2;
 use ratatui::prelude::*;
 use ratatui::{
-    crossterm::event::{self, Event, KeyCode},
-    widgets::{Block, Paragraph},
+    crossterm::event::{self, Event},
+    widgets::{Block, Clear, Paragraph},
     Frame,
 };
 
+/// The least room the side panel needs to be worth showing
+const PANEL: PanelSize = PanelSize {
+    width: 30,
+    height: 6,
+};
+
 pub fn view(model: &mut Model, frame: &mut Frame) {
-    let game_window = &mut model.game.window;
+    let game_window = &model.game.window;
 
     let main_area = frame.area();
-
-    let [left_area, right_area] =
-        Layout::horizontal([Constraint::Percentage(50), Constraint::Percentage(50)])
-            .areas(main_area);
+    let Some(layout) =
+        board_layout::split(main_area, &game_window.world, game_window.zoom, PANEL)
+    else {
+        board_layout::too_small(frame, &game_window.world);
+        return;
+    };
 
     let title = &game_window.world.name;
     let outer_left_block = Block::bordered().title(title.clone());
-    let inner_left = outer_left_block.inner(left_area);
+    let inner_left = outer_left_block.inner(layout.board);
+
+    frame.render_widget(outer_left_block, layout.board);
+    let mut board = game_window.clone();
+    board.zoom = layout.zoom;
+    frame.render_widget(board, inner_left);
 
-    frame.render_widget(outer_left_block, left_area);
-    frame.render_widget(game_window.clone(), inner_left);
+    if let Some(panel_area) = layout.panel {
+        let text = game_window.debug.join("\n");
+        let heading = format!(
+            "~{}: MOVE the BLOCKS onto the GOALS!~",
+            model.game.collection.title.to_uppercase()
+        );
+        frame.render_widget(
+            Paragraph::new(text).block(Block::bordered().title(heading)),
+            panel_area,
+        );
+    }
 
-    let text = game_window.debug.join("\n");
-    frame.render_widget(
-        Paragraph::new(text).block(
-            Block::bordered().title("~MICROBAN I: MOVE the BLOCKS onto the GOALS!~"),
-        ),
-        right_area,
+    if model.game.help {
+        let text = help_text(model);
+        let height = text.lines().count() as u16 + 2;
+        let width = text
+            .lines()
+            .map(|line| line.chars().count())
+            .max()
+            .unwrap_or(0);
+        let area = popup_area(main_area, width as u16 + 4, height);
+        frame.render_widget(Clear, area);
+        frame.render_widget(
+            Paragraph::new(text).block(Block::bordered().title("~PAUSED~")),
+            area,
+        );
+    }
+}
+
+/// What the help overlay says: the game's keys as they're bound now, the rules, and
+/// whatever the level file has to say about the level
+pub fn help_text(model: &Model) -> String {
+    let game = &model.game;
+    let world = &game.worlds[game.world_index];
+    let mut text = format!(
+        "CONTROLS:\n{}\n{}\nLEVEL: {}\n",
+        model.keymap.help(Screen::Game),
+        copy_text::RULES,
+        world.name
     );
+    // Most files only name the author once, for the whole collection
+    let has_author = world
+        .meta
+        .iter()
+        .any(|(key, _)| key.eq_ignore_ascii_case("Author"));
+    if let Some(author) = game.collection.author.as_ref().filter(|_| !has_author) {
+        text.push_str(&format!("    Author: {}\n", author));
+    }
+    for (key, value) in &world.meta {
+        text.push_str(&format!("    {}: {}\n", key, value));
+    }
+    text
 }
 
-// TODO: make a one sized fits all handler for "idiot mode" which is when a user is validly
-// in capsloc mode like an idiot. this happens more than you'd ever imagine. a key is right next to it!
-pub fn handle_key(key: event::KeyEvent) -> Option<GameAction> {
-    match key.code {
+/// A `width` by `height` box in the middle of `area`, shrunk to fit if it has to be
+fn popup_area(area: Rect, width: u16, height: u16) -> Rect {
+    let width = width.min(area.width);
+    let height = height.min(area.height);
+    Rect {
+        x: area.x + (area.width - width) / 2,
+        y: area.y + (area.height - height) / 2,
+        width,
+        height,
+    }
+}
+
+pub fn handle_key(model: &Model, key: event::KeyEvent) -> Option<GameAction> {
+    let Some(action) = model.keymap.action(Screen::Game, &key) else {
+        return Some(GameAction::None);
+    };
+    // Nothing moves while the help is up, and leaving only closes it
+    if model.game.help {
+        return match action {
+            Action::Help | Action::Quit => Some(GameAction::ToggleHelp),
+            _ => Some(GameAction::None),
+        };
+    }
+    match action {
         // Game state
-        KeyCode::Esc => Some(GameAction::Quit),
-        KeyCode::Char('z') | KeyCode::Char('Z') | KeyCode::Char('u') => {
-            Some(GameAction::Undo)
-        }
-        KeyCode::Char('r') | KeyCode::Char('R') => Some(GameAction::Reset),
+        Action::Quit => Some(GameAction::Quit),
+        Action::Undo => Some(GameAction::Undo),
+        Action::Reset => Some(GameAction::Reset),
+        Action::Reverse => Some(GameAction::ToggleReverse),
+        Action::Animation => Some(GameAction::ToggleAnimation),
+        Action::Help => Some(GameAction::ToggleHelp),
+        Action::CopyLevel => Some(GameAction::CopyLevel),
+        Action::CopyMoves => Some(GameAction::CopyMoves),
 
         // Movement
-        KeyCode::Up | KeyCode::Char('w') | KeyCode::Char('W') => {
-            Some(GameAction::Move(Direction::Up))
-        }
-        KeyCode::Left | KeyCode::Char('a') | KeyCode::Char('A') => {
-            Some(GameAction::Move(Direction::Left))
-        }
-        KeyCode::Down | KeyCode::Char('s') | KeyCode::Char('S') => {
-            Some(GameAction::Move(Direction::Down))
-        }
-        KeyCode::Right | KeyCode::Char('d') | KeyCode::Char('D') => {
-            Some(GameAction::Move(Direction::Right))
-        }
+        Action::Up => Some(GameAction::Move(Direction::Up)),
+        Action::Left => Some(GameAction::Move(Direction::Left)),
+        Action::Down => Some(GameAction::Move(Direction::Down)),
+        Action::Right => Some(GameAction::Move(Direction::Right)),
 
         // View
-        KeyCode::Char('1') => Some(GameAction::ZoomFar),
-        KeyCode::Char('2') => Some(GameAction::ZoomMiddle),
-        KeyCode::Char('3') => Some(GameAction::ZoomClose),
+        Action::ZoomFar => Some(GameAction::ZoomFar),
+        Action::ZoomMiddle => Some(GameAction::ZoomMiddle),
+        Action::ZoomClose => Some(GameAction::ZoomClose),
 
         _ => Some(GameAction::None),
     }
@@ -72,22 +150,80 @@ pub fn handle_key(key: event::KeyEvent) -> Option<GameAction> {
 
 pub fn update(model: &mut Model, msg: GameAction) -> Option<GameAction> {
     let game = &mut model.game;
+    // Whatever the last copy or paste said is old news once something else happens
+    if !matches!(msg, GameAction::None) {
+        game.message = None;
+    }
     match msg {
-        GameAction::Quit => model.running_state = RunningState::LevelSelect,
+        GameAction::Quit => {
+            store_progress(model);
+            model.running_state = RunningState::LevelSelect;
+        }
+        GameAction::Move(direction) if game.reverse.is_some() => {
+            let from = game.window.drawn_positions();
+            let steps = game.history.len();
+            reverse::update(game, direction);
+            // Finishing in reverse swaps in the forward game, there's nothing to slide
+            if game.reverse.is_some() && game.history.len() > steps {
+                game.window.slide_from(from);
+            }
+        }
         GameAction::Move(direction) => {
-            if let Some(new_level) = handle_move(&game.window.world, direction) {
-                game.history.push(game.window.world.clone());
-                game.window.world = new_level;
+            let from = game.window.drawn_positions();
+            if play_move(game, direction) {
+                game.window.slide_from(from);
             }
         }
         GameAction::Undo => {
             if let Some(prev_level) = game.history.pop() {
                 game.window.world = prev_level;
+                game.window.tween = None;
+                game.moves.pop();
             }
         }
+        GameAction::ToggleReverse => {
+            reverse::toggle(game);
+            game.window.tween = None;
+        }
+        GameAction::ToggleAnimation => {
+            game.window.animate = !game.window.animate;
+            model.save_file.options.animations = game.window.animate;
+        }
         GameAction::Reset => {
             game.refresh_window();
         }
+        GameAction::ToggleHelp => {
+            game.help = !game.help;
+            // Time spent reading the help doesn't count against the level
+            if game.help {
+                game.clock.stop();
+            } else {
+                game.clock.resume();
+            }
+        }
+        GameAction::CopyLevel => {
+            let world = &game.worlds[game.world_index];
+            let text = format!("; {}\n{}", world.name, to_xsb(world));
+            game.message = Some(copied(clipboard::copy(&text), "Level"));
+        }
+        GameAction::CopyMoves => {
+            // In reverse mode it's the forward game's moves that make a solution
+            let moves = game
+                .reverse
+                .as_ref()
+                .map_or(&game.moves, |forward| &forward.moves);
+            game.message = Some(if moves.is_empty() {
+                "No moves to copy yet".to_string()
+            } else {
+                // The level is copied as it is in its file, so the moves are too
+                copied(clipboard::copy(&game.level_moves(moves)), "Moves")
+            });
+        }
+        GameAction::Paste(text) => {
+            if let Err(e) = open_pasted(model, &text) {
+                model.game.message = Some(format!("Couldn't load that: {}", e));
+            }
+        }
         GameAction::ZoomClose => game.window.zoom = Zoom::Close,
         GameAction::ZoomMiddle => game.window.zoom = Zoom::Middle,
         GameAction::ZoomFar => game.window.zoom = Zoom::Far,
@@ -97,93 +233,288 @@ pub fn update(model: &mut Model, msg: GameAction) -> Option<GameAction> {
     None
 }
 
+/// What to tell the player after trying to copy `what`
+fn copied(result: io::Result<()>, what: &str) -> String {
+    match result {
+        Ok(()) => format!("{} copied to the clipboard", what),
+        Err(e) => format!("Couldn't copy: {}", e),
+    }
+}
+
+/// Opens levels pasted into the terminal as a collection of their own, keeping what
+/// was played of the level they replace
+pub fn open_pasted(model: &mut Model, text: &str) -> Result<(), String> {
+    let (collection, worlds) =
+        collection::from_contents(collection::PASTED, &pasted_contents(text))?;
+    store_progress(model);
+    let count = worlds.len();
+    model.game.symmetry = Symmetry::IDENTITY;
+    model
+        .game
+        .open_collection(&CollectionFile { collection, worlds }, 0);
+    model.game.message = Some(match count {
+        1 => "Playing the pasted level".to_string(),
+        count => format!("Playing the first of {} pasted levels", count),
+    });
+    Ok(())
+}
+
+/// Pasted text the way a level file would have it. Terminals often send the line breaks
+/// of a paste as `\r`, and a board copied off a web page rarely has a `;` title.
+fn pasted_contents(text: &str) -> String {
+    let text = text.replace("\r\n", "\n").replace('\r', "\n");
+    let board_row = |line: &str| {
+        line.contains('#') && line.chars().all(|ch| "#@$.*+ ".contains(ch))
+    };
+    let mut lines: Vec<&str> = text.lines().collect();
+    match lines
+        .iter()
+        .position(|line| line.starts_with(';') || board_row(line))
+    {
+        Some(first) if !lines[first].starts_with(';') => {
+            lines.insert(first, "; Pasted");
+            lines.join("\n")
+        }
+        _ => text,
+    }
+}
+
 pub fn handle_event(model: &mut Model) -> io::Result<Option<GameAction>> {
+    let best = model
+        .level_progress(model.game.world_index)
+        .and_then(|level| level.solution.as_ref())
+        .map_or("X".to_string(), |solution| solution.len().to_string());
+    let help_key = model.keymap.key(Screen::Game, Action::Help);
+
     let window = &mut model.game.window;
     window.debug = Vec::new();
-
     window.debug.push(format!(
         "\n                Steps: {:?}
-        Best Solution: X
-        \ntbd... ",
-        &model.game.history.len()
+        Best Solution: {}
+        \n        Press {} for Help",
+        &model.game.history.len(),
+        best,
+        help_key
     ));
+    if let Some(message) = &model.game.message {
+        window.debug.push(format!("\n        {}", message));
+    }
+    if model.game.symmetry != Symmetry::IDENTITY {
+        window.debug.push("\n        MIRRORED".to_string());
+    }
+    if model.game.reverse.is_some() {
+        window.debug.push(
+            "\n        REVERSE MODE: PULL the BOXES back to where they started!"
+                .to_string(),
+        );
+    }
 
-    if event::poll(Duration::from_millis(50))? {
-        if let Event::Key(key) = event::read()? {
-            if key.kind == event::KeyEventKind::Press {
-                return Ok(handle_key(key));
+    // Redraw often enough for smooth movement while something is sliding
+    let timeout = if window.is_animating() { 16 } else { 50 };
+    if event::poll(Duration::from_millis(timeout))? {
+        match event::read()? {
+            Event::Key(key) if key.kind == event::KeyEventKind::Press => {
+                return Ok(handle_key(model, key));
+            }
+            // Nothing gets swapped in under the help
+            Event::Paste(text) if !model.game.help => {
+                return Ok(Some(GameAction::Paste(text)));
             }
+            _ => {}
         }
     }
 
     // Prevent handling key events, coincidentally, because it's solved!
-    if window.world.is_sokoban_solved() {
+    // (A reverse game starts with every box on a goal, so it doesn't count.)
+    if model.game.reverse.is_none() && window.world.is_sokoban_solved() {
         return Ok(Some(GameAction::Win));
     }
     Ok(None)
 }
 
-fn handle_move(prev_level: &World, direction: Direction) -> Option<World> {
-    let mut player_move = None;
-    let mut level = prev_level.clone();
-
-    // First we find the player and figure out what its new coords will be.
-    // if the player is trying to move into a wall we'll do nothing otherwise we'll
-    // set the move
-    for (index, entity) in level.entities.iter().enumerate() {
-        if let Entity::Player(player) = entity {
-            let new_position = get_new_position(player.position.clone(), &direction);
+/// Plays one forward move, keeping the world it left in the undo history. Returns false
+/// if the move was blocked.
+fn play_move(game: &mut Game, direction: Direction) -> bool {
+    let (new_level, outcome) = apply_move(&game.window.world, direction);
+    if outcome.is_blocked() {
+        return false;
+    }
+    game.history
+        .push(std::mem::replace(&mut game.window.world, new_level));
+    game.moves.push(lurd::to_char(direction, outcome.is_push()));
+    true
+}
 
-            match level.board[[new_position.y, new_position.x]] {
-                Tile::Wall => player_move = None,
-                _ => player_move = Some((index, new_position)),
-            }
-            break;
-        }
-    }
-
-    let mut soko_box_move = None;
-    if let Some((_, player_position)) = player_move.clone() {
-        for (index, entity) in level.entities.iter().enumerate() {
-            if let Entity::SokoBox(soko_box) = entity {
-                // if there is a soko_box where the player wants to move see if we can
-                // push it.
-                if soko_box.position == player_position.clone() {
-                    let new_position =
-                        get_new_position(soko_box.position.clone(), &direction);
-
-                    if level.is_tile_occupied(&new_position) {
-                        // if the tile we are trying to move too is occupied both moves are
-                        // invalid.
-                        soko_box_move = None;
-                        player_move = None;
-                    } else {
-                        // otherwise move the soko_box
-                        soko_box_move = Some((index, new_position.clone()));
-                    }
-                }
-            }
+/// Plays `moves` from the current position one at a time, so every one of them can be
+/// undone. Stops at the first move that can't be played and returns how many were.
+pub fn play_moves(game: &mut Game, moves: &str) -> usize {
+    let mut played = 0;
+    for ch in moves.chars() {
+        match lurd::from_char(ch) {
+            Some((direction, _)) if play_move(game, direction) => played += 1,
+            _ => break,
         }
     }
+    played
+}
 
-    // resolve the movement
-    if let Some((index, new_position)) = player_move {
-        if let Entity::Player(ref mut player) = &mut level.entities[index] {
-            player.position = new_position.clone();
+/// Opens the level under the level select cursor. Moves left over from the last time
+/// it was played are replayed, unless the player asked to start `fresh`.
+pub fn start_level(model: &mut Model, fresh: bool) {
+    let index = model.game.world_index;
+    let moves = if fresh {
+        if model.level_progress(index).is_some() {
+            model.level_progress_mut(index).in_progress = None;
         }
+        None
     } else {
-        return None;
+        model
+            .level_progress(index)
+            .and_then(|level| level.in_progress.clone())
+    };
+
+    model.game.change_level(model.game.world_index);
+    if let Some(moves) = moves {
+        let moves = transform::lurd(&moves, model.game.symmetry);
+        play_moves(&mut model.game, &moves);
+    }
+    model.running_state = RunningState::Game;
+}
+
+/// Remembers the moves played on the current level so it can be picked up again later.
+/// Only the forward game is kept, a level left in reverse mode resumes going forwards.
+pub fn store_progress(model: &mut Model) {
+    let game = &model.game;
+    let moves = game.level_moves(
+        game.reverse
+            .as_ref()
+            .map_or(&game.moves, |forward| &forward.moves),
+    );
+    let index = game.world_index;
+    // Don't start keeping progress on a level just because it was looked at
+    if moves.is_empty() && model.level_progress(index).is_none() {
+        return;
+    }
+    model.level_progress_mut(index).in_progress = (!moves.is_empty()).then_some(moves);
+}
+
+/// Applies a single move to `world` and reports what happened.
+///
+/// This is a pure function: `world` is left untouched and the world after the move is
+/// returned alongside the `MoveOutcome`. When the move is blocked the returned world is an
+/// unchanged copy of the input.
+pub fn apply_move(world: &World, direction: Direction) -> (World, MoveOutcome) {
+    let mut level = world.clone();
+
+    let Some(player_index) = level
+        .entities
+        .iter()
+        .position(|entity| matches!(entity, Entity::Player(_)))
+    else {
+        // Nobody to move, a world without a player can't go anywhere
+        return (level, MoveOutcome::BlockedByWall);
+    };
+
+    let from = level.entities[player_index].get_position();
+    let to = get_new_position(from.clone(), &direction);
+    if is_wall(&level, &to) {
+        return (level, MoveOutcome::BlockedByWall);
     }
-    if let Some((index, new_position)) = soko_box_move {
-        if let Entity::SokoBox(ref mut soko_box) = &mut level.entities[index] {
-            soko_box.position = new_position.clone();
+
+    // If there is a soko_box where the player wants to move see if we can push it.
+    let pushed_box = level
+        .entities
+        .iter()
+        .enumerate()
+        .filter(|(_, entity)| matches!(entity, Entity::SokoBox(_)))
+        .enumerate()
+        .find(|(_, (_, entity))| entity.get_position() == to)
+        .map(|(box_index, (entity_index, _))| (box_index, entity_index));
+
+    let Some((box_index, entity_index)) = pushed_box else {
+        if let Entity::Player(ref mut player) = level.entities[player_index] {
+            player.position = to.clone();
+        }
+        return (level, MoveOutcome::Walked { from, to });
+    };
+
+    let box_from = to.clone();
+    let box_to = get_new_position(box_from.clone(), &direction);
+    // If the tile we are trying to push the box onto is occupied both moves are invalid
+    if is_wall(&level, &box_to) || level.is_tile_occupied(&box_to) {
+        return (level, MoveOutcome::BlockedByBox);
+    }
+    if let Entity::SokoBox(ref mut soko_box) = level.entities[entity_index] {
+        soko_box.position = box_to.clone();
+    }
+    if let Entity::Player(ref mut player) = level.entities[player_index] {
+        player.position = to;
+    }
+
+    let outcome = if level.is_sokoban_solved() {
+        MoveOutcome::Solved {
+            box_index,
+            from: box_from,
+            to: box_to,
+        }
+    } else {
+        MoveOutcome::Pushed {
+            box_index,
+            from: box_from,
+            to: box_to,
+        }
+    };
+    (level, outcome)
+}
+
+/// Finds the shortest walk for the player from `from` to `to` that doesn't push anything
+pub fn player_path(
+    world: &World,
+    from: &Coordinate,
+    to: &Coordinate,
+) -> Option<Vec<Direction>> {
+    // For every square reached, the direction the player was heading when it got there
+    let mut came_from: Array2<Option<Direction>> =
+        Array2::from_elem(world.board.dim(), None);
+    let mut queue = VecDeque::from([from.clone()]);
+
+    while let Some(position) = queue.pop_front() {
+        if position == *to {
+            let mut path = Vec::new();
+            let mut position = position;
+            while position != *from {
+                let direction = came_from[position.arr_index()]?;
+                path.push(direction);
+                position = get_new_position(position, &direction.opposite());
+            }
+            path.reverse();
+            return Some(path);
+        }
+        for direction in Direction::ALL {
+            let next = get_new_position(position.clone(), &direction);
+            if next == *from
+                || is_wall(world, &next)
+                || world.is_tile_occupied(&next)
+                || came_from[next.arr_index()].is_some()
+            {
+                continue;
+            }
+            came_from[next.arr_index()] = Some(direction);
+            queue.push_back(next);
         }
     }
+    None
+}
 
-    Some(level)
+/// Anything off the edge of the board is treated as a wall
+fn is_wall(world: &World, position: &Coordinate) -> bool {
+    !matches!(
+        world.board.get(position.arr_index()),
+        Some(Tile::Floor | Tile::Goal | Tile::Empty)
+    )
 }
 
-fn get_new_position(position: Coordinate, direction: &Direction) -> Coordinate {
+pub fn get_new_position(position: Coordinate, direction: &Direction) -> Coordinate {
     match direction {
         Direction::Up => Coordinate {
             x: position.x,
@@ -0,0 +1,169 @@
+#[cfg(test)]
+mod tests {
+    use crate::collection;
+    use crate::soko_game::{apply_move, open_pasted, play_moves};
+    use crate::soko_loader::to_xsb;
+    use crate::test_util::{load_world, new_game, new_model};
+    use crate::types::{Coordinate, Direction, Entity, MoveOutcome, World};
+
+    fn player_position(world: &World) -> Coordinate {
+        world
+            .entities
+            .iter()
+            .find(|ent| matches!(ent, Entity::Player(_)))
+            .map(|ent| ent.get_position())
+            .unwrap()
+    }
+
+    const LEVEL: &str = "; Test
+######
+#    #
+# $@ #
+# $  #
+#.. ##
+####
+";
+
+    #[test]
+    fn test_walk_moves_the_player() {
+        let world = load_world(LEVEL);
+        let (next, outcome) = apply_move(&world, Direction::Right);
+
+        assert_eq!(
+            outcome,
+            MoveOutcome::Walked {
+                from: Coordinate { x: 3, y: 2 },
+                to: Coordinate { x: 4, y: 2 },
+            }
+        );
+        assert_eq!(player_position(&next), Coordinate { x: 4, y: 2 });
+        // The original world is left alone
+        assert_eq!(player_position(&world), Coordinate { x: 3, y: 2 });
+    }
+
+    #[test]
+    fn test_wall_blocks_the_player() {
+        let world = load_world(LEVEL);
+        let (next, outcome) = apply_move(&world, Direction::Right);
+        let (next, outcome_2) = apply_move(&next, Direction::Right);
+
+        assert!(!outcome.is_blocked());
+        assert_eq!(outcome_2, MoveOutcome::BlockedByWall);
+        assert_eq!(player_position(&next), Coordinate { x: 4, y: 2 });
+    }
+
+    #[test]
+    fn test_push_reports_the_box_that_moved() {
+        let world = load_world(LEVEL);
+        let (next, outcome) = apply_move(&world, Direction::Left);
+
+        assert_eq!(
+            outcome,
+            MoveOutcome::Pushed {
+                box_index: 0,
+                from: Coordinate { x: 2, y: 2 },
+                to: Coordinate { x: 1, y: 2 },
+            }
+        );
+        assert_eq!(player_position(&next), Coordinate { x: 2, y: 2 });
+    }
+
+    #[test]
+    fn test_box_behind_box_blocks_the_push() {
+        let world = load_world(LEVEL);
+        let (next, _) = apply_move(&world, Direction::Up);
+        let (next, _) = apply_move(&next, Direction::Left);
+        let (next, outcome) = apply_move(&next, Direction::Down);
+
+        assert_eq!(outcome, MoveOutcome::BlockedByBox);
+        assert_eq!(player_position(&next), Coordinate { x: 2, y: 1 });
+    }
+
+    #[test]
+    fn test_box_against_wall_blocks_the_push() {
+        let world = load_world(LEVEL);
+        let (next, _) = apply_move(&world, Direction::Left);
+        let (_, outcome) = apply_move(&next, Direction::Left);
+
+        assert_eq!(outcome, MoveOutcome::BlockedByBox);
+    }
+
+    #[test]
+    fn test_last_push_solves_the_level() {
+        let world = load_world(
+            "; Almost
+#####
+#@$.#
+#####
+",
+        );
+        let (next, outcome) = apply_move(&world, Direction::Right);
+
+        assert_eq!(
+            outcome,
+            MoveOutcome::Solved {
+                box_index: 0,
+                from: Coordinate { x: 2, y: 1 },
+                to: Coordinate { x: 3, y: 1 },
+            }
+        );
+        assert!(next.is_sokoban_solved());
+    }
+
+    #[test]
+    fn test_play_moves_rebuilds_the_undo_history() {
+        let mut game = new_game(load_world(LEVEL));
+
+        assert_eq!(play_moves(&mut game, "urd"), 3);
+        assert_eq!(game.moves, "urd");
+        assert_eq!(game.history.len(), 3);
+        assert_eq!(
+            player_position(&game.window.world),
+            Coordinate { x: 4, y: 2 }
+        );
+        // Each step can be undone back to the start
+        assert_eq!(player_position(&game.history[1]), Coordinate { x: 3, y: 1 });
+        assert_eq!(player_position(&game.history[0]), Coordinate { x: 3, y: 2 });
+    }
+
+    #[test]
+    fn test_play_moves_stops_at_a_blocked_move() {
+        let mut game = new_game(load_world(LEVEL));
+
+        // The second push would shove the box into the wall
+        assert_eq!(play_moves(&mut game, "LLd"), 1);
+        assert_eq!(game.moves, "L");
+        assert_eq!(game.history.len(), 1);
+    }
+
+    #[test]
+    fn test_pasted_level_with_carriage_returns() {
+        let mut model = new_model();
+        open_pasted(&mut model, "; Tiny\r#####\r#@$.#\r#####\r").unwrap();
+        assert_eq!(model.game.collection.name, collection::PASTED);
+        assert_eq!(model.game.worlds.len(), 1);
+        assert_eq!(model.game.worlds[0].name, "Tiny");
+        assert_eq!(to_xsb(&model.game.window.world), "#####\n#@$.#\n#####\n");
+        assert!(model.game.message.is_some());
+    }
+
+    #[test]
+    fn test_pasted_board_without_a_title() {
+        let mut model = new_model();
+        open_pasted(&mut model, "#####\r\n#@$.#\r\n#####\r\n").unwrap();
+        assert_eq!(model.game.worlds.len(), 1);
+        assert_eq!(model.game.worlds[0].name, "Pasted");
+
+        // A file header stays a header, the title goes in just above the board
+        open_pasted(&mut model, "Title: Mine\n\n#####\n#@$.#\n#####\n").unwrap();
+        assert_eq!(model.game.collection.title, "Mine");
+        assert_eq!(model.game.worlds[0].name, "Pasted");
+    }
+
+    #[test]
+    fn test_pasting_something_else_keeps_the_level() {
+        let mut model = new_model();
+        assert!(open_pasted(&mut model, "https://example.com/level").is_err());
+        assert_eq!(model.game.collection.name, "test");
+    }
+}
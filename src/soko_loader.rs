@@ -25,6 +25,8 @@ use ndarray::Array2;
 #[derive(Debug, Clone)]
 enum Token {
     Text(String),
+    /// A `Key: value` line, like `Title: Microban` at the top of a file
+    Meta(String, String),
     Wall,
     Player,
     SokoBox,
@@ -51,6 +53,10 @@ fn tokenize(contents: &str) -> Option<Tokens> {
         if line.is_empty() {
             continue;
         }
+        if let Some((key, value)) = metadata(line) {
+            tokens.push(Token::Meta(key, value));
+            continue;
+        }
         for ch in line.chars() {
             match ch {
                 '#' => tokens.push(Token::Wall),
@@ -68,6 +74,16 @@ fn tokenize(contents: &str) -> Option<Tokens> {
     Some(tokens)
 }
 
+/// Splits a `Key: value` line. Keys are words, so a board row can never be taken for one.
+fn metadata(line: &str) -> Option<(String, String)> {
+    let (key, value) = line.split_once(':')?;
+    let is_key = key.starts_with(|ch: char| ch.is_ascii_alphabetic())
+        && key
+            .chars()
+            .all(|ch| ch.is_ascii_alphanumeric() || ch == ' ');
+    is_key.then(|| (key.trim().to_string(), value.trim().to_string()))
+}
+
 fn get_board_dimensions(tokens: &[Token]) -> (usize, usize) {
     let mut x = 0;
     let y = tokens
@@ -118,8 +134,21 @@ fn group_sokoban_tokens(tokens: &[Token]) -> Vec<Vec<Token>> {
 fn parse_sokoban_level(tokens: &[Token]) -> Result<World, String> {
     match tokens {
         [Token::Text(title), level_toks @ ..] => {
+            let meta = level_toks
+                .iter()
+                .filter_map(|tok| match tok {
+                    Token::Meta(key, value) => Some((key.clone(), value.clone())),
+                    _ => None,
+                })
+                .collect();
+            let level_toks: Vec<Token> = level_toks
+                .iter()
+                .filter(|tok| !matches!(tok, Token::Meta(..)))
+                .cloned()
+                .collect();
+
             // Dimensions for the board
-            let (x, y) = get_board_dimensions(level_toks);
+            let (x, y) = get_board_dimensions(&level_toks);
 
             // Create an initial board with default values (e.g., all `Wall`)
             let mut board = Array2::from_elem((y, x), Tile::Floor);
@@ -173,6 +202,7 @@ fn parse_sokoban_level(tokens: &[Token]) -> Result<World, String> {
                 name: title.to_string(),
                 board,
                 entities,
+                meta,
                 camera_position: Coordinate { x: 0, y: 0 },
             };
             Ok(level)
@@ -255,6 +285,19 @@ pub fn cull_tiles(index: (usize, usize), board: &mut Array2<Tile>) -> &Array2<Ti
     board
 }
 
+/// The `Key: value` lines at the top of a level file, before the first level
+pub fn parse_header(sokoban_text: &str) -> Vec<(String, String)> {
+    tokenize(sokoban_text)
+        .unwrap_or_default()
+        .into_iter()
+        .take_while(|token| !matches!(token, Token::Text(_)))
+        .filter_map(|token| match token {
+            Token::Meta(key, value) => Some((key, value)),
+            _ => None,
+        })
+        .collect()
+}
+
 pub fn parse_sokoban_worlds(sokoban_text: &str) -> Result<Vec<World>, String> {
     if let Some(tokens) = tokenize(sokoban_text) {
         let worlds: Vec<World> = group_sokoban_tokens(&tokens)
@@ -269,3 +312,40 @@ pub fn parse_sokoban_worlds(sokoban_text: &str) -> Result<Vec<World>, String> {
         Err("Level failed to load".to_string())
     }
 }
+
+/// Writes a world back out as a level in the standard text format, without its title.
+/// Trailing blanks are left off each row.
+pub fn to_xsb(world: &World) -> String {
+    let mut rows: Vec<Vec<char>> = world
+        .board
+        .rows()
+        .into_iter()
+        .map(|row| {
+            row.iter()
+                .map(|tile| match tile {
+                    Tile::Wall => '#',
+                    Tile::Goal => '.',
+                    Tile::Floor | Tile::Empty => ' ',
+                })
+                .collect()
+        })
+        .collect();
+
+    for entity in world.entities.iter() {
+        let Coordinate { x, y } = entity.get_position();
+        let on_goal = rows[y][x] == '.';
+        rows[y][x] = match (entity, on_goal) {
+            (Entity::Player(_), false) => '@',
+            (Entity::Player(_), true) => '+',
+            (Entity::SokoBox(_), false) => '$',
+            (Entity::SokoBox(_), true) => '*',
+        };
+    }
+
+    rows.iter()
+        .map(|row| {
+            let row: String = row.iter().collect();
+            format!("{}\n", row.trim_end())
+        })
+        .collect()
+}
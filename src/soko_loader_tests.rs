@@ -315,4 +315,73 @@ mod tests {
 
         assert_eq!(board, expected);
     }
+
+    #[test]
+    fn test_to_xsb_writes_the_level_back_out() {
+        let level = "  ####
+###  ####
+#     $ #
+# #  #$ #
+# . .#@ #
+#########
+";
+        let worlds = parse_sokoban_worlds(&format!("; 3\n\n{}", level)).unwrap();
+        assert_eq!(to_xsb(&worlds[0]), level);
+    }
+
+    #[test]
+    fn test_to_xsb_marks_things_on_goals() {
+        let level = "#####
+#+*.#
+#####
+";
+        let worlds = parse_sokoban_worlds(&format!("; Goals\n{}", level)).unwrap();
+        assert_eq!(to_xsb(&worlds[0]), level);
+    }
+
+    #[test]
+    fn test_metadata_lines_are_kept_out_of_the_board() {
+        let text = "Title: Tiny
+Author: Someone
+
+; 1
+#####
+#@$.#
+#####
+Comment: An easy one
+";
+        assert_eq!(
+            parse_header(text),
+            vec![
+                ("Title".to_string(), "Tiny".to_string()),
+                ("Author".to_string(), "Someone".to_string()),
+            ]
+        );
+        let worlds = parse_sokoban_worlds(text).unwrap();
+        assert_eq!(worlds.len(), 1);
+        assert_eq!(worlds[0].board.dim(), (3, 5));
+    }
+
+    #[test]
+    fn test_levels_keep_their_own_metadata() {
+        let text = "Author: Someone
+
+; 1
+#####
+#@$.#
+#####
+Comment: An easy one
+
+; 2
+#####
+#.$@#
+#####
+";
+        let worlds = parse_sokoban_worlds(text).unwrap();
+        assert_eq!(
+            worlds[0].meta,
+            vec![("Comment".to_string(), "An easy one".to_string())]
+        );
+        assert!(worlds[1].meta.is_empty());
+    }
 }
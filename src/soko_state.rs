@@ -0,0 +1,296 @@
+/*!
+ * Description:
+ *
+ * A compact representation of a Sokoban position for searching (solving, hinting and
+ * deadlock analysis).
+ *
+ * A `World` keeps its entities in a `Vec` and is cloned wholesale on every move. Here the
+ * parts of a level that never change (walls, goals, the board itself) live once in a
+ * `StaticBoard`, and each position is a `SokoState`: the player's cell index plus a bitset
+ * of the cells holding boxes. Every state carries a zobrist hash that is updated
+ * incrementally as the player walks and pushes, so states can be dropped into a `HashSet`
+ * without rehashing the whole board.
+ *
+ * Usage:
+ *
+ *   let board = StaticBoard::from_world(&world);
+ *   let state = SokoState::from_world(&board, &world);
+ *   if let Some((next, pushed)) = state.try_move(&board, Direction::Up) { ... }
+ *   let world = next.to_world(&board);
+ *
+ * Notes:
+ *
+ * Cells are numbered row by row, `index = y * width + x`.
+ */
+
+use std::hash::{Hash, Hasher};
+
+use crate::types::{
+    Board, Coordinate, Direction, Entity, Player, SokoBox, Tile, World,
+};
+
+/// A fixed size set of cell indexes stored one bit per cell
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct CellSet {
+    words: Vec<u64>,
+}
+
+impl CellSet {
+    pub fn new(cells: usize) -> CellSet {
+        CellSet {
+            words: vec![0; cells.div_ceil(64)],
+        }
+    }
+
+    pub fn contains(&self, cell: usize) -> bool {
+        self.words[cell / 64] & (1 << (cell % 64)) != 0
+    }
+
+    pub fn insert(&mut self, cell: usize) {
+        self.words[cell / 64] |= 1 << (cell % 64);
+    }
+
+    pub fn remove(&mut self, cell: usize) {
+        self.words[cell / 64] &= !(1 << (cell % 64));
+    }
+
+    #[allow(dead_code)] // Only the tests count cells so far
+    pub fn len(&self) -> usize {
+        self.words
+            .iter()
+            .map(|word| word.count_ones() as usize)
+            .sum()
+    }
+
+    /// Iterates over the cells in the set in ascending order
+    pub fn iter(&self) -> impl Iterator<Item = usize> + '_ {
+        self.words.iter().enumerate().flat_map(|(wi, &word)| {
+            (0..64)
+                .filter(move |bit| word & (1 << bit) != 0)
+                .map(move |bit| wi * 64 + bit)
+        })
+    }
+
+    /// True when every cell in `self` is also in `other`
+    pub fn is_subset(&self, other: &CellSet) -> bool {
+        self.words
+            .iter()
+            .zip(other.words.iter())
+            .all(|(mine, theirs)| mine & !theirs == 0)
+    }
+}
+
+/// Everything about a level that no move can change
+#[derive(Debug, Clone)]
+pub struct StaticBoard {
+    // Kept for turning states back into worlds, which only the tests do so far
+    #[allow(dead_code)]
+    pub name: String,
+    #[allow(dead_code)]
+    pub board: Board,
+    pub width: usize,
+    pub height: usize,
+    pub walls: CellSet,
+    pub goals: CellSet,
+    zobrist_boxes: Vec<u64>,
+    zobrist_player: Vec<u64>,
+}
+
+impl StaticBoard {
+    pub fn from_world(world: &World) -> StaticBoard {
+        let (height, width) = world.board.dim();
+        let cells = width * height;
+        let mut walls = CellSet::new(cells);
+        let mut goals = CellSet::new(cells);
+        for ((y, x), tile) in world.board.indexed_iter() {
+            let index = y * width + x;
+            match tile {
+                Tile::Wall => walls.insert(index),
+                Tile::Goal => goals.insert(index),
+                Tile::Floor | Tile::Empty => {}
+            }
+        }
+
+        // The tables only need to be distinct and well mixed, not unpredictable, so a
+        // fixed seed keeps hashes stable from run to run.
+        let mut seed = 0x5EED_50C0_BA11_u64;
+        let zobrist_boxes = (0..cells).map(|_| splitmix64(&mut seed)).collect();
+        let zobrist_player = (0..cells).map(|_| splitmix64(&mut seed)).collect();
+
+        StaticBoard {
+            name: world.name.clone(),
+            board: world.board.clone(),
+            width,
+            height,
+            walls,
+            goals,
+            zobrist_boxes,
+            zobrist_player,
+        }
+    }
+
+    pub fn cells(&self) -> usize {
+        self.width * self.height
+    }
+
+    pub fn index(&self, coord: &Coordinate) -> usize {
+        coord.y * self.width + coord.x
+    }
+
+    #[allow(dead_code)]
+    pub fn coordinate(&self, index: usize) -> Coordinate {
+        Coordinate {
+            x: index % self.width,
+            y: index / self.width,
+        }
+    }
+
+    /// The cell one step from `index` in `direction`, if that stays on the board
+    pub fn neighbour(&self, index: usize, direction: Direction) -> Option<usize> {
+        let (x, y) = (index % self.width, index / self.width);
+        match direction {
+            Direction::Left if x > 0 => Some(index - 1),
+            Direction::Right if x + 1 < self.width => Some(index + 1),
+            Direction::Up if y > 0 => Some(index - self.width),
+            Direction::Down if y + 1 < self.height => Some(index + self.width),
+            _ => None,
+        }
+    }
+
+    /// True for cells that a player or box could ever stand on
+    pub fn is_open(&self, index: usize) -> bool {
+        !self.walls.contains(index)
+    }
+}
+
+/// A single position in a level: where the player is and which cells hold boxes
+#[derive(Debug, Clone)]
+pub struct SokoState {
+    pub player: usize,
+    pub boxes: CellSet,
+    zobrist: u64,
+}
+
+impl SokoState {
+    pub fn new(board: &StaticBoard, player: usize, boxes: CellSet) -> SokoState {
+        let zobrist = boxes
+            .iter()
+            .fold(board.zobrist_player[player], |hash, cell| {
+                hash ^ board.zobrist_boxes[cell]
+            });
+        SokoState {
+            player,
+            boxes,
+            zobrist,
+        }
+    }
+
+    pub fn from_world(board: &StaticBoard, world: &World) -> SokoState {
+        let mut player = 0;
+        let mut boxes = CellSet::new(board.cells());
+        for entity in world.entities.iter() {
+            let index = board.index(&entity.get_position());
+            match entity {
+                Entity::Player(_) => player = index,
+                Entity::SokoBox(_) => boxes.insert(index),
+            }
+        }
+        SokoState::new(board, player, boxes)
+    }
+
+    /// Builds a full `World` for this state. The player comes first in the entity list,
+    /// followed by the boxes in cell order.
+    #[allow(dead_code)]
+    pub fn to_world(&self, board: &StaticBoard) -> World {
+        let player = Entity::Player(Player {
+            position: board.coordinate(self.player),
+        });
+        let boxes = self.boxes.iter().map(|cell| {
+            Entity::SokoBox(SokoBox {
+                position: board.coordinate(cell),
+            })
+        });
+
+        World {
+            name: board.name.clone(),
+            board: board.board.clone(),
+            entities: std::iter::once(player).chain(boxes).collect(),
+            meta: Vec::new(),
+            camera_position: Coordinate { x: 0, y: 0 },
+        }
+    }
+
+    #[allow(dead_code)] // `Hash` reads the field itself, only the tests call this
+    pub fn zobrist(&self) -> u64 {
+        self.zobrist
+    }
+
+    pub fn is_solved(&self, board: &StaticBoard) -> bool {
+        self.boxes.is_subset(&board.goals)
+    }
+
+    /// Moves the player one step, pushing a box if there is one in the way.
+    ///
+    /// Returns the new state and whether a box was pushed, or `None` when the move is
+    /// blocked.
+    pub fn try_move(
+        &self,
+        board: &StaticBoard,
+        direction: Direction,
+    ) -> Option<(SokoState, bool)> {
+        let to = board.neighbour(self.player, direction)?;
+        if !board.is_open(to) {
+            return None;
+        }
+
+        let mut next = self.clone();
+        next.set_player(board, to);
+        if !self.boxes.contains(to) {
+            return Some((next, false));
+        }
+
+        let box_to = board.neighbour(to, direction)?;
+        if !board.is_open(box_to) || self.boxes.contains(box_to) {
+            return None;
+        }
+        next.move_box(board, to, box_to);
+        Some((next, true))
+    }
+
+    pub fn set_player(&mut self, board: &StaticBoard, player: usize) {
+        self.zobrist ^=
+            board.zobrist_player[self.player] ^ board.zobrist_player[player];
+        self.player = player;
+    }
+
+    pub fn move_box(&mut self, board: &StaticBoard, from: usize, to: usize) {
+        self.boxes.remove(from);
+        self.boxes.insert(to);
+        self.zobrist ^= board.zobrist_boxes[from] ^ board.zobrist_boxes[to];
+    }
+}
+
+impl PartialEq for SokoState {
+    fn eq(&self, other: &Self) -> bool {
+        self.zobrist == other.zobrist
+            && self.player == other.player
+            && self.boxes == other.boxes
+    }
+}
+
+impl Eq for SokoState {}
+
+impl Hash for SokoState {
+    fn hash<H: Hasher>(&self, state: &mut H) {
+        state.write_u64(self.zobrist);
+    }
+}
+
+/// Small, fast pseudo random generator (Steele, Lea & Flood's SplitMix64)
+pub fn splitmix64(state: &mut u64) -> u64 {
+    *state = state.wrapping_add(0x9E37_79B9_7F4A_7C15);
+    let mut z = *state;
+    z = (z ^ (z >> 30)).wrapping_mul(0xBF58_476D_1CE4_E5B9);
+    z = (z ^ (z >> 27)).wrapping_mul(0x94D0_49BB_1331_11EB);
+    z ^ (z >> 31)
+}
@@ -0,0 +1,124 @@
+#[cfg(test)]
+mod tests {
+    use std::collections::HashSet;
+
+    use crate::soko_game::apply_move;
+    use crate::soko_state::{CellSet, SokoState, StaticBoard};
+    use crate::test_util::load_world;
+    use crate::types::{Coordinate, Direction, Entity, World};
+
+    const LEVEL: &str = "; Test
+######
+#    #
+# $@ #
+# $  #
+#.. ##
+####
+";
+
+    fn positions(world: &World) -> (Coordinate, Vec<(usize, usize)>) {
+        let mut player = Coordinate { x: 0, y: 0 };
+        let mut boxes = Vec::new();
+        for entity in world.entities.iter() {
+            match entity {
+                Entity::Player(p) => player = p.position.clone(),
+                Entity::SokoBox(b) => boxes.push((b.position.y, b.position.x)),
+            }
+        }
+        boxes.sort();
+        (player, boxes)
+    }
+
+    #[test]
+    fn test_cell_set_insert_remove_and_iterate() {
+        let mut set = CellSet::new(130);
+        set.insert(3);
+        set.insert(64);
+        set.insert(129);
+        assert!(set.contains(64));
+        assert_eq!(set.len(), 3);
+
+        set.remove(64);
+        assert!(!set.contains(64));
+        assert_eq!(set.iter().collect::<Vec<_>>(), vec![3, 129]);
+    }
+
+    #[test]
+    fn test_round_trip_through_world() {
+        let world = load_world(LEVEL);
+        let board = StaticBoard::from_world(&world);
+        let state = SokoState::from_world(&board, &world);
+
+        assert_eq!(state.boxes.len(), 2);
+        assert_eq!(positions(&state.to_world(&board)), positions(&world));
+        assert_eq!(state.to_world(&board).board, world.board);
+    }
+
+    #[test]
+    fn test_moves_match_apply_move() {
+        let world = load_world(LEVEL);
+        let board = StaticBoard::from_world(&world);
+        let mut state = SokoState::from_world(&board, &world);
+        let mut world = world;
+
+        let moves = [
+            Direction::Up,
+            Direction::Left,
+            Direction::Down,
+            Direction::Left,
+            Direction::Right,
+            Direction::Down,
+            Direction::Left,
+        ];
+        for direction in moves {
+            let (next_world, outcome) = apply_move(&world, direction);
+            match state.try_move(&board, direction) {
+                Some((next_state, _)) => {
+                    assert!(!outcome.is_blocked());
+                    state = next_state;
+                }
+                None => assert!(outcome.is_blocked()),
+            }
+            world = next_world;
+            assert_eq!(positions(&state.to_world(&board)), positions(&world));
+        }
+    }
+
+    #[test]
+    fn test_incremental_hash_matches_fresh_hash() {
+        let world = load_world(LEVEL);
+        let board = StaticBoard::from_world(&world);
+        let state = SokoState::from_world(&board, &world);
+
+        let (pushed, did_push) = state.try_move(&board, Direction::Left).unwrap();
+        assert!(did_push);
+
+        let fresh = SokoState::new(&board, pushed.player, pushed.boxes.clone());
+        assert_eq!(pushed.zobrist(), fresh.zobrist());
+        assert_ne!(pushed.zobrist(), state.zobrist());
+
+        // Walking away and back again lands on the same state
+        let (away, _) = state.try_move(&board, Direction::Right).unwrap();
+        let (back, _) = away.try_move(&board, Direction::Left).unwrap();
+        let mut seen = HashSet::new();
+        seen.insert(state);
+        assert!(!seen.insert(back));
+    }
+
+    #[test]
+    fn test_solved_when_every_box_is_on_a_goal() {
+        let world = load_world(
+            "; Almost
+#####
+#@$.#
+#####
+",
+        );
+        let board = StaticBoard::from_world(&world);
+        let state = SokoState::from_world(&board, &world);
+        assert!(!state.is_solved(&board));
+
+        let (state, _) = state.try_move(&board, Direction::Right).unwrap();
+        assert!(state.is_solved(&board));
+    }
+}
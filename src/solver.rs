@@ -0,0 +1,122 @@
+/*!
+ * Description:
+ *
+ * Finds the shortest solution to a level, counted in moves.
+ *
+ * A breadth first search over `SokoState`s: every position one move from the start, then
+ * every position two moves away and so on, so the first solved position found is reached
+ * in as few moves as possible. Pushes onto a dead square (one a box can never be brought
+ * back to a goal from) are never tried.
+ *
+ * Usage:
+ *
+ *   match solver::solve(&world, solver::DEFAULT_LIMIT) {
+ *       Some(lurd) => println!("{} moves", lurd.len()),
+ *       None => println!("no solution found"),
+ *   }
+ *
+ * Notes:
+ *
+ * Large levels can have more positions than fit in memory, so the search gives up after
+ * `limit` positions. `None` means no solution was found within that many, not that there
+ * isn't one.
+ */
+
+use std::collections::{HashSet, VecDeque};
+
+use crate::lurd;
+use crate::soko_state::{CellSet, SokoState, StaticBoard};
+use crate::types::{Direction, World};
+
+/// Enough for most of the bundled levels, small enough to answer in a moment. The
+/// biggest ones in Microban II need more than memory allows.
+pub const DEFAULT_LIMIT: usize = 200_000;
+
+const DIRECTIONS: [Direction; 4] = [
+    Direction::Up,
+    Direction::Down,
+    Direction::Left,
+    Direction::Right,
+];
+
+/// A move optimal solution in LURD notation, if one turns up within `limit` positions
+pub fn solve(world: &World, limit: usize) -> Option<String> {
+    let board = StaticBoard::from_world(world);
+    let start = SokoState::from_world(&board, world);
+    if start.is_solved(&board) {
+        return Some(String::new());
+    }
+    let live = live_cells(&board);
+
+    // Every position seen, with the position and move it was first reached from
+    let mut states: Vec<(SokoState, usize, char)> = vec![(start.clone(), 0, ' ')];
+    let mut seen = HashSet::from([start]);
+    let mut queue = VecDeque::from([0]);
+
+    while let Some(from) = queue.pop_front() {
+        for direction in DIRECTIONS {
+            let Some((next, pushed)) = states[from].0.try_move(&board, direction)
+            else {
+                continue;
+            };
+            let dead = |cell| !live.contains(cell);
+            if pushed && board.neighbour(next.player, direction).is_some_and(dead) {
+                continue;
+            }
+            if !seen.insert(next.clone()) {
+                continue;
+            }
+            let solved = next.is_solved(&board);
+            states.push((next, from, lurd::to_char(direction, pushed)));
+            if solved {
+                return Some(path(&states, states.len() - 1));
+            }
+            if states.len() >= limit {
+                return None;
+            }
+            queue.push_back(states.len() - 1);
+        }
+    }
+    None
+}
+
+/// The moves from the start to `states[index]`
+fn path(states: &[(SokoState, usize, char)], mut index: usize) -> String {
+    let mut moves = Vec::new();
+    while index != 0 {
+        let (_, from, ch) = &states[index];
+        moves.push(*ch);
+        index = *from;
+    }
+    moves.iter().rev().collect()
+}
+
+/// The cells a box can still reach a goal from: every cell a box could be pulled to,
+/// starting from each goal
+pub fn live_cells(board: &StaticBoard) -> CellSet {
+    let mut live = CellSet::new(board.cells());
+    let mut queue: VecDeque<usize> = board.goals.iter().collect();
+    for goal in board.goals.iter() {
+        live.insert(goal);
+    }
+    while let Some(cell) = queue.pop_front() {
+        for direction in DIRECTIONS {
+            // Pulling a box from `cell` to `to` needs the player on the far side of `to`
+            let pulled = board
+                .neighbour(cell, direction)
+                .filter(|&to| board.is_open(to))
+                .filter(|&to| {
+                    board
+                        .neighbour(to, direction)
+                        .is_some_and(|player| board.is_open(player))
+                });
+            if let Some(to) = pulled {
+                if !live.contains(to) {
+                    live.insert(to);
+                    queue.push_back(to);
+                }
+            }
+        }
+    }
+    live
+}
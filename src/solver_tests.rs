@@ -0,0 +1,92 @@
+#[cfg(test)]
+mod tests {
+    use crate::soko_game::apply_move;
+    use crate::soko_state::StaticBoard;
+    use crate::solver::{live_cells, solve};
+    use crate::test_util::load_world;
+    use crate::types::World;
+    use crate::{lurd, types::Coordinate};
+
+    const LEVEL: &str = "; Test
+######
+#    #
+# $@ #
+# $  #
+#.. ##
+####
+";
+
+    fn play(world: &World, moves: &str) -> World {
+        lurd::parse(moves)
+            .unwrap()
+            .into_iter()
+            .fold(world.clone(), |world, direction| {
+                apply_move(&world, direction).0
+            })
+    }
+
+    #[test]
+    fn test_solution_solves_the_level() {
+        let world = load_world(LEVEL);
+        let solution = solve(&world, 10_000).unwrap();
+
+        assert!(play(&world, &solution).is_sokoban_solved());
+    }
+
+    #[test]
+    fn test_solution_is_the_shortest() {
+        let world = load_world(
+            "; Corridor
+#######
+#@ $ .#
+#######
+",
+        );
+        assert_eq!(solve(&world, 10_000).as_deref(), Some("rRR"));
+    }
+
+    #[test]
+    fn test_solved_level_needs_no_moves() {
+        let world = load_world(
+            "; Done
+#####
+#@*.#
+#####
+",
+        );
+        // The box already on a goal leaves one goal free, but no box is off a goal
+        assert_eq!(solve(&world, 10).as_deref(), Some(""));
+    }
+
+    #[test]
+    fn test_gives_up_past_the_limit() {
+        let world = load_world(LEVEL);
+        assert_eq!(solve(&world, 5), None);
+    }
+
+    #[test]
+    fn test_unsolvable_level_has_no_solution() {
+        let world = load_world(
+            "; Stuck
+######
+#$  .#
+#@   #
+######
+",
+        );
+        assert_eq!(solve(&world, 10_000), None);
+    }
+
+    #[test]
+    fn test_corners_off_goals_are_dead() {
+        let world = load_world(LEVEL);
+        let board = StaticBoard::from_world(&world);
+        let live = live_cells(&board);
+        let cell = |x, y| board.index(&Coordinate { x, y });
+
+        assert!(live.contains(cell(1, 4)));
+        assert!(live.contains(cell(2, 2)));
+        assert!(!live.contains(cell(1, 1)));
+        assert!(!live.contains(cell(4, 1)));
+    }
+}
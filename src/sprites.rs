@@ -197,3 +197,33 @@ pub fn get_player_sprite_4() -> Sprite {
     );
     Sprite { chars }
 }
+
+/// The 4 wide player mid stride. Even and odd frames lead with opposite feet.
+pub fn get_player_sprite_4_walk(frame: usize) -> Sprite {
+    let hair1 = Color::Rgb(52, 32, 33);
+    let skin = Color::Rgb(239, 204, 165);
+    let cloth1 = Color::Rgb(8, 25, 61);
+    let cloth2 = Color::Rgb(37, 75, 75);
+    let cloth3 = Color::Rgb(56, 109, 82);
+    let (left_foot, right_foot) = if frame.is_multiple_of(2) {
+        ('▘', '▗')
+    } else {
+        ('▖', '▝')
+    };
+    #[rustfmt::skip]
+    let chars = arr2(
+        &[
+            [CharPixel {char: '▝', fg: Some(hair1),           bg: None},
+             CharPixel {char: '▅', fg: Some(skin),            bg: Some(hair1)},
+             CharPixel {char: '▂', fg: Some(skin),            bg: Some(hair1)},
+             CharPixel {char: '▙', fg: Some(hair1),           bg: None},
+            ],
+            [CharPixel {char: left_foot, fg: Some(cloth1),    bg: None},
+             CharPixel {char: '▚', fg: Some(cloth3),          bg: Some(cloth2)},
+             CharPixel {char: '▞', fg: Some(cloth2),          bg: Some(cloth3)},
+             CharPixel {char: right_foot, fg: Some(cloth2),   bg: None},
+            ],
+        ]
+    );
+    Sprite { chars }
+}
@@ -0,0 +1,130 @@
+/*!
+ * Description:
+ *
+ * Where termban keeps its files, and writing them without losing anything.
+ *
+ * Saves live in the platform data directory, `$XDG_DATA_HOME/termban` (usually
+ * `~/.local/share/termban`), unless `TERMBAN_DATA_DIR` points somewhere else.
+ *
+ * Writes go to a temporary file next to the real one which is then renamed over it, so
+ * a crash or a full disk part way through leaves the old save intact. The previous
+ * version is kept alongside as `saves.toml.bak`. A save that can't be read at all is
+ * renamed out of the way rather than overwritten.
+ */
+
+use serde::Serialize;
+use std::ffi::OsString;
+use std::fs::{self, File};
+use std::io::{self, Read, Write};
+use std::path::{Path, PathBuf};
+use std::time::{SystemTime, UNIX_EPOCH};
+
+pub const SAVE_FILENAME: &str = "saves.toml";
+
+/// Set this to keep termban's data somewhere other than the platform data directory
+pub const DATA_DIR_VAR: &str = "TERMBAN_DATA_DIR";
+
+pub fn data_dir() -> PathBuf {
+    data_dir_from(|var| std::env::var_os(var))
+}
+
+/// Where the data lives, given a way to look up environment variables
+pub fn data_dir_from(lookup: impl Fn(&str) -> Option<OsString>) -> PathBuf {
+    let from_env = |var: &str| lookup(var).filter(|value| !value.is_empty());
+
+    if let Some(dir) = from_env(DATA_DIR_VAR) {
+        PathBuf::from(dir)
+    } else if let Some(dir) = from_env("XDG_DATA_HOME") {
+        PathBuf::from(dir).join("termban")
+    } else if let Some(dir) = from_env("APPDATA") {
+        PathBuf::from(dir).join("termban")
+    } else if let Some(home) = from_env("HOME") {
+        PathBuf::from(home).join(".local/share/termban")
+    } else {
+        PathBuf::from(".")
+    }
+}
+
+pub fn save_path() -> PathBuf {
+    data_dir().join(SAVE_FILENAME)
+}
+
+/// Older versions kept the save in whatever directory the game was started from
+fn legacy_save_path() -> PathBuf {
+    PathBuf::from(SAVE_FILENAME)
+}
+
+/// `path` with `suffix` stuck on the end of the file name
+fn with_suffix(path: &Path, suffix: &str) -> PathBuf {
+    let mut name = path.file_name().map(OsString::from).unwrap_or_default();
+    name.push(suffix);
+    path.with_file_name(name)
+}
+
+pub fn backup_path(path: &Path) -> PathBuf {
+    with_suffix(path, ".bak")
+}
+
+pub fn read_file(path: &Path) -> Result<String, io::Error> {
+    let mut file = File::open(path)?;
+    let mut contents = String::new();
+    file.read_to_string(&mut contents)?;
+    Ok(contents)
+}
+
+/// Reads the save at `path`. If there isn't one yet but an older version left a save in
+/// the working directory, that one is moved into place first.
+pub fn read_save_file(path: &Path) -> Result<String, io::Error> {
+    let legacy = legacy_save_path();
+    if !path.exists() && legacy.exists() {
+        write_atomic(path, &read_file(&legacy)?)?;
+        fs::rename(&legacy, backup_path(&legacy))?;
+    }
+    read_file(path)
+}
+
+/// Replaces the contents of `path` all at once, keeping the old contents as a backup
+pub fn write_atomic(path: &Path, contents: &str) -> Result<(), io::Error> {
+    if let Some(dir) = path.parent().filter(|dir| !dir.as_os_str().is_empty()) {
+        fs::create_dir_all(dir)?;
+    }
+
+    let temp_path = with_suffix(path, ".tmp");
+    let mut file = File::create(&temp_path)?;
+    file.write_all(contents.as_bytes())?;
+    // Make sure the new contents are really on disk before the old ones are replaced
+    file.sync_all()?;
+    drop(file);
+
+    if path.exists() {
+        fs::copy(path, backup_path(path))?;
+    }
+    fs::rename(&temp_path, path)
+}
+
+pub fn save_toml_file<T: Serialize>(path: &Path, toml: &T) -> Result<(), io::Error> {
+    // Serialize the struct to a TOML string
+    let toml_string = toml::to_string(&toml)
+        .map_err(|e| io::Error::new(io::ErrorKind::InvalidData, e))?;
+    write_atomic(path, &toml_string)
+}
+
+/// Moves a save that couldn't be read out of the way, so starting afresh doesn't
+/// overwrite it. Returns where it went.
+pub fn set_aside(path: &Path) -> Result<PathBuf, io::Error> {
+    let seconds = SystemTime::now()
+        .duration_since(UNIX_EPOCH)
+        .map(|elapsed| elapsed.as_secs())
+        .unwrap_or_default();
+    let aside = with_suffix(path, &format!(".unreadable-{}", seconds));
+    fs::rename(path, &aside)?;
+    Ok(aside)
+}
+
+/// Erases the save by moving it to the backup, so one slip of the finger can be undone
+pub fn delete_save_file(path: &Path) -> Result<(), io::Error> {
+    match fs::rename(path, backup_path(path)) {
+        Err(e) if e.kind() == io::ErrorKind::NotFound => Ok(()),
+        result => result,
+    }
+}
@@ -0,0 +1,116 @@
+#[cfg(test)]
+mod tests {
+    use crate::storage::*;
+    use std::ffi::OsString;
+    use std::fs;
+    use std::path::PathBuf;
+
+    /// A fresh scratch directory for one test
+    fn scratch_dir(name: &str) -> PathBuf {
+        let dir = std::env::temp_dir().join(format!(
+            "termban-{}-{}",
+            name,
+            std::process::id()
+        ));
+        let _ = fs::remove_dir_all(&dir);
+        dir
+    }
+
+    #[test]
+    fn test_write_atomic_creates_the_directory() {
+        let dir = scratch_dir("create");
+        let path = dir.join("nested").join(SAVE_FILENAME);
+
+        write_atomic(&path, "first").unwrap();
+        assert_eq!(read_file(&path).unwrap(), "first");
+        // Nothing to back up the first time around
+        assert!(!backup_path(&path).exists());
+
+        fs::remove_dir_all(dir).unwrap();
+    }
+
+    #[test]
+    fn test_write_atomic_keeps_a_backup() {
+        let dir = scratch_dir("backup");
+        let path = dir.join(SAVE_FILENAME);
+
+        write_atomic(&path, "first").unwrap();
+        write_atomic(&path, "second").unwrap();
+        assert_eq!(read_file(&path).unwrap(), "second");
+        assert_eq!(read_file(&backup_path(&path)).unwrap(), "first");
+
+        // The temporary file has been renamed away
+        let names: Vec<_> = fs::read_dir(&dir)
+            .unwrap()
+            .map(|entry| entry.unwrap().file_name())
+            .collect();
+        assert_eq!(names.len(), 2);
+
+        fs::remove_dir_all(dir).unwrap();
+    }
+
+    #[test]
+    fn test_delete_moves_the_save_to_the_backup() {
+        let dir = scratch_dir("delete");
+        let path = dir.join(SAVE_FILENAME);
+
+        write_atomic(&path, "progress").unwrap();
+        delete_save_file(&path).unwrap();
+        assert!(!path.exists());
+        assert_eq!(read_file(&backup_path(&path)).unwrap(), "progress");
+
+        // Deleting again is fine
+        delete_save_file(&path).unwrap();
+
+        fs::remove_dir_all(dir).unwrap();
+    }
+
+    #[test]
+    fn test_set_aside_keeps_the_unreadable_save() {
+        let dir = scratch_dir("aside");
+        let path = dir.join(SAVE_FILENAME);
+
+        write_atomic(&path, "garbage").unwrap();
+        let aside = set_aside(&path).unwrap();
+        assert!(!path.exists());
+        assert_eq!(read_file(&aside).unwrap(), "garbage");
+
+        fs::remove_dir_all(dir).unwrap();
+    }
+
+    /// An environment with just `vars` set
+    fn environment(vars: &[(&str, &str)]) -> impl Fn(&str) -> Option<OsString> {
+        let vars: Vec<(String, OsString)> = vars
+            .iter()
+            .map(|(name, value)| (name.to_string(), OsString::from(value)))
+            .collect();
+        move |name| {
+            vars.iter()
+                .find(|(var, _)| var == name)
+                .map(|(_, value)| value.clone())
+        }
+    }
+
+    #[test]
+    fn test_data_dir_can_be_overridden() {
+        let home = [("HOME", "/home/me"), ("XDG_DATA_HOME", "/data")];
+        assert_eq!(
+            data_dir_from(environment(&[(DATA_DIR_VAR, "/tmp/somewhere-else")])),
+            PathBuf::from("/tmp/somewhere-else")
+        );
+        assert_eq!(
+            data_dir_from(environment(&home)),
+            PathBuf::from("/data/termban")
+        );
+        assert_eq!(
+            data_dir_from(environment(&home[..1])),
+            PathBuf::from("/home/me/.local/share/termban")
+        );
+        // Set but empty is the same as not set
+        assert_eq!(
+            data_dir_from(environment(&[(DATA_DIR_VAR, ""), ("HOME", "/home/me")])),
+            PathBuf::from("/home/me/.local/share/termban")
+        );
+        assert_eq!(data_dir_from(environment(&[])), PathBuf::from("."));
+    }
+}
@@ -0,0 +1,96 @@
+/*!
+ * Description:
+ *
+ * The screen shown after the last level of a collection: how much of it has been
+ * solved, and the moves and pushes of the best solutions added up. From here the player
+ * goes back to the collection browser or the level select.
+ *
+ * Usage:
+ *
+ *   model.running_state = RunningState::Summary;
+ */
+
+use std::io;
+use std::time::Duration;
+
+use crate::collection_select;
+use crate::keymap::{Action, Screen};
+use crate::types::{Model, RunningState, SummaryAction};
+use ratatui::{
+    crossterm::event::{self, Event},
+    widgets::{Block, Paragraph},
+    Frame,
+};
+
+pub fn view(model: &mut Model, frame: &mut Frame) {
+    let collection = &model.game.collection;
+    let totals = model.save_file.current().totals(collection);
+    let heading = if totals.solved == totals.levels {
+        format!("You solved every level in {}!", collection.title)
+    } else {
+        format!(
+            "That's the end of {}, with {} level(s) still to solve.",
+            collection.title,
+            totals.levels - totals.solved
+        )
+    };
+    let author = collection
+        .author
+        .as_ref()
+        .map_or(String::new(), |author| format!("    by {}\n", author));
+    let text = format!(
+        "
+    {}
+{}
+    Solved:           {} of {}
+    Skipped:          {}
+
+    Your best solutions add up to
+    Moves:            {}
+    Pushes:           {}
+
+{}",
+        heading,
+        author,
+        totals.solved,
+        totals.levels,
+        totals.skipped,
+        totals.moves,
+        totals.pushes,
+        model.keymap.help(Screen::Summary),
+    );
+    frame.render_widget(
+        Paragraph::new(text).block(Block::bordered().title("~COLLECTION COMPLETE~")),
+        frame.area(),
+    );
+}
+
+pub fn update(model: &mut Model, msg: SummaryAction) -> Option<SummaryAction> {
+    match msg {
+        SummaryAction::Collections => {
+            collection_select::refresh(model);
+            model.running_state = RunningState::CollectionSelect;
+        }
+        SummaryAction::Levels => model.running_state = RunningState::LevelSelect,
+    };
+    None
+}
+
+pub fn handle_event(model: &Model) -> io::Result<Option<SummaryAction>> {
+    if event::poll(Duration::from_millis(250))? {
+        if let Event::Key(key) = event::read()? {
+            if key.kind == event::KeyEventKind::Press {
+                return Ok(handle_key(model, key));
+            }
+        }
+    }
+    Ok(None)
+}
+
+pub fn handle_key(model: &Model, key: event::KeyEvent) -> Option<SummaryAction> {
+    match model.keymap.action(Screen::Summary, &key)? {
+        Action::Collections => Some(SummaryAction::Collections),
+        Action::Levels => Some(SummaryAction::Levels),
+        _ => None,
+    }
+}
@@ -0,0 +1,32 @@
+/*!
+ * Description:
+ *
+ * Fixtures shared by the tests, so a new field or a new way of loading a level is
+ * changed in one place rather than in every `*_tests.rs` file.
+ */
+
+use crate::collection::from_contents;
+use crate::soko_loader::parse_sokoban_worlds;
+use crate::types::{Collection, Game, Model, RunningState, SaveFile, World};
+
+/// The first level in `level`, which has to load
+pub fn load_world(level: &str) -> World {
+    parse_sokoban_worlds(level).unwrap().remove(0)
+}
+
+/// A game of just `world`, on its first move
+pub fn new_game(world: World) -> Game {
+    Game::new(vec![world], Collection::default(), 0)
+}
+
+/// A model playing the first level of a collection of one corridor, with the
+/// collection known to the save file
+pub fn new_model() -> Model {
+    let (collection, worlds) =
+        from_contents("test", "; Corridor\n#######\n#@ $ .#\n#######\n").unwrap();
+    let mut save_file = SaveFile::new();
+    save_file.adopt_collection(&collection);
+    let mut model = Model::new(Game::new(worlds, collection, 0), save_file);
+    model.running_state = RunningState::Game;
+    model
+}
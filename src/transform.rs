@@ -0,0 +1,244 @@
+/*!
+ * Description:
+ *
+ * Turning and flipping levels. A level can be rotated by quarter turns or mirrored left
+ * to right, which with both together gives the eight ways a square can be laid down. A
+ * solution to a level is transformed the same way to get a solution to the turned level.
+ *
+ * Normalizing a level trims away the empty rows and columns around it and puts its
+ * entities in a standard order, so two copies of a level that only differ in layout
+ * noise come out the same. The canonical form goes further and picks one of the eight
+ * ways to lay it down, so turned and mirrored copies come out the same too.
+ *
+ * Usage:
+ *
+ *   let flipped = world.transformed(Symmetry::MIRROR);
+ *   let moves = transform::lurd(&solution, Symmetry::MIRROR);
+ *
+ * Notes:
+ *
+ * A `Symmetry` mirrors first, then turns clockwise. Rotating keeps the order of the
+ * entities, so box numbers still line up with the original level. Normalizing doesn't.
+ */
+
+use ndarray::{s, Array2, ArrayView1};
+
+use crate::lurd;
+use crate::soko_loader::to_xsb;
+use crate::types::{Coordinate, Direction, Entity, Player, SokoBox, Tile, World};
+
+/// One of the eight ways to lay a level down: mirrored left to right or not, then
+/// turned clockwise `turns` quarter turns
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq, Hash)]
+pub struct Symmetry {
+    pub mirror: bool,
+    pub turns: u8,
+}
+
+impl Symmetry {
+    pub const IDENTITY: Symmetry = Symmetry {
+        mirror: false,
+        turns: 0,
+    };
+    pub const MIRROR: Symmetry = Symmetry {
+        mirror: true,
+        turns: 0,
+    };
+
+    pub const ALL: [Symmetry; 8] = [
+        Symmetry::rotation(0),
+        Symmetry::rotation(1),
+        Symmetry::rotation(2),
+        Symmetry::rotation(3),
+        Symmetry::mirror_rotation(0),
+        Symmetry::mirror_rotation(1),
+        Symmetry::mirror_rotation(2),
+        Symmetry::mirror_rotation(3),
+    ];
+
+    pub const fn rotation(turns: u8) -> Symmetry {
+        Symmetry {
+            mirror: false,
+            turns: turns % 4,
+        }
+    }
+
+    pub const fn mirror_rotation(turns: u8) -> Symmetry {
+        Symmetry {
+            mirror: true,
+            turns: turns % 4,
+        }
+    }
+
+    /// The symmetry that puts a transformed level back the way it was
+    pub fn inverse(self) -> Symmetry {
+        // Mirroring reverses which way the turns go, so a mirrored one undoes itself
+        if self.mirror {
+            self
+        } else {
+            Symmetry::rotation(4 - self.turns)
+        }
+    }
+
+    /// Where a move in `direction` goes once the level is transformed
+    pub fn direction(self, direction: Direction) -> Direction {
+        let direction = if self.mirror {
+            match direction {
+                Direction::Left => Direction::Right,
+                Direction::Right => Direction::Left,
+                other => other,
+            }
+        } else {
+            direction
+        };
+        (0..self.turns).fold(direction, |direction, _| clockwise(direction))
+    }
+}
+
+fn clockwise(direction: Direction) -> Direction {
+    match direction {
+        Direction::Up => Direction::Right,
+        Direction::Right => Direction::Down,
+        Direction::Down => Direction::Left,
+        Direction::Left => Direction::Up,
+    }
+}
+
+/// A LURD solution transformed to match a level transformed by `symmetry`. Pushes stay
+/// pushes and anything that isn't a move is kept as it was.
+pub fn lurd(moves: &str, symmetry: Symmetry) -> String {
+    moves
+        .chars()
+        .map(|ch| match lurd::from_char(ch) {
+            Some((direction, push)) => {
+                lurd::to_char(symmetry.direction(direction), push)
+            }
+            None => ch,
+        })
+        .collect()
+}
+
+fn place(entity: &Entity, position: Coordinate) -> Entity {
+    match entity {
+        Entity::Player(_) => Entity::Player(Player { position }),
+        Entity::SokoBox(_) => Entity::SokoBox(SokoBox { position }),
+    }
+}
+
+impl World {
+    /// The level turned clockwise by `turns` quarter turns
+    pub fn rotated(&self, turns: u8) -> World {
+        (0..turns % 4).fold(self.clone(), |world, _| world.quarter_turn())
+    }
+
+    /// The level flipped left to right
+    pub fn mirrored(&self) -> World {
+        let (_, width) = self.board.dim();
+        let mut world = self.clone();
+        world.board = Array2::from_shape_fn(self.board.dim(), |(y, x)| {
+            self.board[[y, width - 1 - x]].clone()
+        });
+        world.entities = self
+            .entities
+            .iter()
+            .map(|entity| {
+                let Coordinate { x, y } = entity.get_position();
+                place(
+                    entity,
+                    Coordinate {
+                        x: width - 1 - x,
+                        y,
+                    },
+                )
+            })
+            .collect();
+        world
+    }
+
+    pub fn transformed(&self, symmetry: Symmetry) -> World {
+        if symmetry.mirror {
+            self.mirrored().rotated(symmetry.turns)
+        } else {
+            self.rotated(symmetry.turns)
+        }
+    }
+
+    fn quarter_turn(&self) -> World {
+        let (height, width) = self.board.dim();
+        let mut world = self.clone();
+        // The left column becomes the top row
+        world.board = Array2::from_shape_fn((width, height), |(y, x)| {
+            self.board[[height - 1 - x, y]].clone()
+        });
+        world.entities = self
+            .entities
+            .iter()
+            .map(|entity| {
+                let Coordinate { x, y } = entity.get_position();
+                place(
+                    entity,
+                    Coordinate {
+                        x: height - 1 - y,
+                        y: x,
+                    },
+                )
+            })
+            .collect();
+        world
+    }
+
+    /// The level with the empty rows and columns around it trimmed off, the player
+    /// first among the entities and the boxes after in reading order
+    pub fn normalized(&self) -> World {
+        let used =
+            |tiles: ArrayView1<Tile>| tiles.iter().any(|tile| *tile != Tile::Empty);
+        let rows: Vec<usize> = (0..self.board.nrows())
+            .filter(|&y| used(self.board.row(y)))
+            .collect();
+        let columns: Vec<usize> = (0..self.board.ncols())
+            .filter(|&x| used(self.board.column(x)))
+            .collect();
+
+        let mut world = self.clone();
+        world.camera_position = Coordinate { x: 0, y: 0 };
+        let (Some(&top), Some(&bottom), Some(&left), Some(&right)) =
+            (rows.first(), rows.last(), columns.first(), columns.last())
+        else {
+            return world;
+        };
+        world.board = self.board.slice(s![top..=bottom, left..=right]).to_owned();
+        world.entities = self
+            .entities
+            .iter()
+            .map(|entity| {
+                let Coordinate { x, y } = entity.get_position();
+                place(
+                    entity,
+                    Coordinate {
+                        x: x - left,
+                        y: y - top,
+                    },
+                )
+            })
+            .collect();
+        world.entities.sort_by_key(|entity| {
+            let Coordinate { x, y } = entity.get_position();
+            (matches!(entity, Entity::SokoBox(_)), y, x)
+        });
+        world
+    }
+
+    /// The level normalized and laid down whichever of the eight ways has the layout
+    /// that sorts first
+    pub fn canonical(&self) -> World {
+        let world = self.normalized();
+        Symmetry::ALL
+            .iter()
+            .map(|&symmetry| {
+                let turned = world.transformed(symmetry).normalized();
+                (to_xsb(&turned), turned)
+            })
+            .min_by(|(a, _), (b, _)| a.cmp(b))
+            .map_or(world, |(_, turned)| turned)
+    }
+}
@@ -0,0 +1,124 @@
+#[cfg(test)]
+mod tests {
+    use crate::replay;
+    use crate::soko_loader::to_xsb;
+    use crate::test_util::load_world;
+    use crate::transform::{lurd, Symmetry};
+    use crate::types::{RunningState, World};
+
+    // Lopsided, so every symmetry lays it down differently
+    const LEVEL: &str = "; Test
+#####
+#@  #
+# $ ##
+#.   #
+######
+";
+    const SOLUTION: &str = "rDrdL";
+
+    fn solves(world: &World, moves: &str) -> bool {
+        replay::load(world, moves, RunningState::Menu)
+            .is_ok_and(|replay| replay.worlds.last().unwrap().is_sokoban_solved())
+    }
+
+    #[test]
+    fn test_rotate_and_mirror() {
+        let world = load_world(LEVEL);
+        assert_eq!(
+            to_xsb(&world.rotated(1)),
+            "#####\n#. @#\n# $ #\n#   #\n# ###\n###\n"
+        );
+        assert_eq!(
+            to_xsb(&world.mirrored()),
+            " #####\n #  @#\n## $ #\n#   .#\n######\n"
+        );
+        // Four quarter turns, or mirroring twice, is back where we started
+        assert_eq!(to_xsb(&world.rotated(4)), to_xsb(&world));
+        assert_eq!(to_xsb(&world.rotated(1).rotated(3)), to_xsb(&world));
+        assert_eq!(to_xsb(&world.mirrored().mirrored()), to_xsb(&world));
+    }
+
+    #[test]
+    fn test_every_symmetry_is_different_and_undoes() {
+        let world = load_world(LEVEL);
+        let mut layouts: Vec<String> = Symmetry::ALL
+            .iter()
+            .map(|&symmetry| to_xsb(&world.transformed(symmetry)))
+            .collect();
+        for symmetry in Symmetry::ALL {
+            let back = world.transformed(symmetry).transformed(symmetry.inverse());
+            assert_eq!(to_xsb(&back), to_xsb(&world), "{:?}", symmetry);
+        }
+        layouts.sort();
+        layouts.dedup();
+        assert_eq!(layouts.len(), 8);
+    }
+
+    #[test]
+    fn test_solutions_transform_with_the_level() {
+        let world = load_world(LEVEL);
+        assert!(solves(&world, SOLUTION));
+        for symmetry in Symmetry::ALL {
+            let moves = lurd(SOLUTION, symmetry);
+            assert!(
+                solves(&world.transformed(symmetry), &moves),
+                "{:?}",
+                symmetry
+            );
+            assert_eq!(lurd(&moves, symmetry.inverse()), SOLUTION);
+        }
+        assert_eq!(lurd("rDrdL", Symmetry::MIRROR), "lDldR");
+        assert_eq!(lurd("rDrdL", Symmetry::rotation(1)), "dLdlU");
+    }
+
+    #[test]
+    fn test_normalize_trims_and_orders() {
+        let mut world = load_world("; Test\n\n   #####\n   #$@.#\n   #####\n");
+        // Pad the board out with an empty row and column on every side
+        let (height, width) = world.board.dim();
+        let mut board = ndarray::Array2::from_elem(
+            (height + 2, width + 2),
+            crate::types::Tile::Empty,
+        );
+        board
+            .slice_mut(ndarray::s![1..=height, 1..=width])
+            .assign(&world.board);
+        world.board = board;
+        for entity in world.entities.iter_mut() {
+            match entity {
+                crate::types::Entity::Player(player) => {
+                    player.position.x += 1;
+                    player.position.y += 1;
+                }
+                crate::types::Entity::SokoBox(soko_box) => {
+                    soko_box.position.x += 1;
+                    soko_box.position.y += 1;
+                }
+            }
+        }
+        world.entities.reverse();
+
+        let normal = world.normalized();
+        assert_eq!(to_xsb(&normal), "#####\n#$@.#\n#####\n");
+        assert!(matches!(
+            normal.entities[0],
+            crate::types::Entity::Player(_)
+        ));
+    }
+
+    #[test]
+    fn test_canonical_is_the_same_however_its_turned() {
+        let world = load_world(LEVEL);
+        let canonical = world.canonical();
+        for symmetry in Symmetry::ALL {
+            let turned = world.transformed(symmetry);
+            assert_eq!(to_xsb(&turned.canonical()), to_xsb(&canonical));
+            assert!(to_xsb(&canonical) <= to_xsb(&turned.normalized()));
+        }
+        assert_eq!(to_xsb(&canonical.canonical()), to_xsb(&canonical));
+        assert!(matches!(
+            canonical.entities[0],
+            crate::types::Entity::Player(_)
+        ));
+    }
+}
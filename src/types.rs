@@ -1,46 +1,412 @@
 use ratatui::style::Color;
+use ratatui::widgets::ListState;
 
 use crate::colors::{get_color, TolColor};
+use crate::keymap::Keymap;
+use crate::lurd;
+use crate::save_schema::CURRENT_VERSION;
+use crate::transform::{self, Symmetry};
 use ndarray::Array2;
 
 use serde::{Deserialize, Serialize};
+use std::collections::BTreeMap;
+use std::time::{Duration, Instant};
 
 #[derive(Serialize, Deserialize, Debug)]
 pub struct SaveFile {
+    /// The layout this file was written with, see `save_schema`
+    pub version: u32,
+    /// One save per profile, there is always at least one
     pub saves: Vec<Save>,
+    /// Index into `saves` of the profile being played
+    #[serde(default)]
+    pub active: usize,
+    /// The name of the collection that was open last, it opens again next time
+    #[serde(default)]
+    pub collection: String,
+    #[serde(default)]
+    pub options: Options,
 }
 impl SaveFile {
     pub fn new() -> Self {
         Self {
-            saves: vec![Save {
-                name: "My Save".to_string(),
-                level: 0,
-            }],
+            version: CURRENT_VERSION,
+            saves: vec![Save::new("My Save")],
+            active: 0,
+            collection: String::new(),
+            options: Options::default(),
         }
     }
+
+    /// The profile being played
+    pub fn current(&self) -> &Save {
+        &self.saves[self.active.min(self.saves.len() - 1)]
+    }
+
+    pub fn current_mut(&mut self) -> &mut Save {
+        let active = self.active.min(self.saves.len() - 1);
+        &mut self.saves[active]
+    }
+
+    /// Adds a new profile and returns its index
+    pub fn add_profile(&mut self, name: &str) -> usize {
+        self.saves.push(Save::new(name));
+        self.saves.len() - 1
+    }
+
+    /// Deletes a profile, keeping the active one selected. Deleting the only profile
+    /// leaves a fresh one in its place.
+    pub fn remove_profile(&mut self, index: usize) {
+        if index >= self.saves.len() {
+            return;
+        }
+        self.saves.remove(index);
+        if self.saves.is_empty() {
+            self.saves.push(Save::new("My Save"));
+        }
+        if self.active > index || self.active >= self.saves.len() {
+            self.active = self.active.saturating_sub(1);
+        }
+    }
+
+    /// Brings every profile's progress on `collection` up to date with its level ids
+    pub fn adopt_collection(&mut self, collection: &Collection) {
+        for save in self.saves.iter_mut() {
+            save.adopt_by_index(collection);
+        }
+    }
+}
+
+/// Settings shared by every save
+#[derive(Serialize, Deserialize, Debug)]
+#[serde(default)]
+pub struct Options {
+    pub animations: bool,
+    /// How levels unlock in each collection, keyed by collection name
+    pub unlock: BTreeMap<String, Unlock>,
+    /// Every level open regardless of the unlock rules, for trying levels out. Set with
+    /// `--develop` and never saved.
+    #[serde(skip)]
+    pub develop: bool,
+}
+
+impl Default for Options {
+    fn default() -> Self {
+        Options {
+            animations: true,
+            unlock: BTreeMap::new(),
+            develop: false,
+        }
+    }
+}
+
+impl Options {
+    pub fn unlock_for(&self, collection: &Collection) -> Unlock {
+        self.unlock
+            .get(&collection.name)
+            .copied()
+            .unwrap_or_default()
+    }
+}
+
+/// Which levels of a collection can be played before the ones ahead of them are done
+#[derive(Serialize, Deserialize, Debug, Clone, Copy, PartialEq, Eq, Default)]
+#[serde(rename_all = "lowercase")]
+pub enum Unlock {
+    /// One level at a time, each opens once every level before it is done
+    #[default]
+    Linear,
+    /// Up to this many unfinished levels open at once
+    Window(usize),
+    /// Everything open from the start
+    Open,
 }
 
 #[derive(Serialize, Deserialize, Debug)]
 pub struct Save {
     pub name: String,
-    pub level: usize,
+    /// Progress in each level collection, keyed by collection name
+    #[serde(default)]
+    pub collections: BTreeMap<String, CollectionProgress>,
+}
+
+impl Save {
+    pub fn new(name: &str) -> Self {
+        Self {
+            name: name.to_string(),
+            collections: BTreeMap::new(),
+        }
+    }
+
+    /// Progress on the level at `index` in `collection`, if it has been played
+    pub fn level(
+        &self,
+        collection: &Collection,
+        index: usize,
+    ) -> Option<&LevelProgress> {
+        let id = collection.level_ids.get(index)?;
+        self.collections.get(&collection.name)?.levels.get(id)
+    }
+
+    /// Progress on the level at `index` in `collection`, starting it if need be
+    pub fn level_mut(
+        &mut self,
+        collection: &Collection,
+        index: usize,
+    ) -> &mut LevelProgress {
+        self.collections
+            .entry(collection.name.clone())
+            .or_default()
+            .levels
+            .entry(collection.level_ids[index].clone())
+            .or_default()
+    }
+
+    pub fn is_solved(&self, collection: &Collection, index: usize) -> bool {
+        self.level(collection, index)
+            .is_some_and(|level| level.solved)
+    }
+
+    /// Solved or skipped
+    pub fn is_finished(&self, collection: &Collection, index: usize) -> bool {
+        self.level(collection, index)
+            .is_some_and(|level| level.solved || level.skipped)
+    }
+
+    /// The position of the first level in `collection` that hasn't been solved or
+    /// skipped, or the number of levels if they all have
+    pub fn first_unfinished(&self, collection: &Collection) -> usize {
+        (0..collection.level_ids.len())
+            .find(|&index| !self.is_finished(collection, index))
+            .unwrap_or(collection.level_ids.len())
+    }
+
+    /// Whether the `unlock` rule lets the level at `index` in `collection` be played yet
+    pub fn is_unlocked(
+        &self,
+        collection: &Collection,
+        unlock: Unlock,
+        index: usize,
+    ) -> bool {
+        let open_at_once = match unlock {
+            Unlock::Open => return true,
+            Unlock::Linear => 1,
+            Unlock::Window(open) => open.max(1),
+        };
+        (0..index)
+            .filter(|&earlier| !self.is_finished(collection, earlier))
+            .count()
+            < open_at_once
+    }
+
+    pub fn solved_count(&self, collection: &Collection) -> usize {
+        (0..collection.level_ids.len())
+            .filter(|&index| self.is_solved(collection, index))
+            .count()
+    }
+
+    /// Adds up the progress on every level in `collection`
+    pub fn totals(&self, collection: &Collection) -> Totals {
+        let mut totals = Totals {
+            levels: collection.level_ids.len(),
+            ..Default::default()
+        };
+        for index in 0..totals.levels {
+            let Some(level) = self.level(collection, index) else {
+                continue;
+            };
+            if level.solved {
+                totals.solved += 1;
+            } else if level.skipped {
+                totals.skipped += 1;
+            }
+            if let Some(solution) = &level.solution {
+                totals.moves += solution.len();
+                totals.pushes += lurd::push_count(solution);
+            }
+        }
+        totals
+    }
+
+    /// Files progress that was recorded by level position (see
+    /// `CollectionProgress::by_index`) under the ids of the levels now at those positions
+    fn adopt_by_index(&mut self, collection: &Collection) {
+        let Some(progress) = self.collections.get_mut(&collection.name) else {
+            return;
+        };
+        for (index, level) in std::mem::take(&mut progress.by_index) {
+            let id = index
+                .parse::<usize>()
+                .ok()
+                .and_then(|index| collection.level_ids.get(index));
+            if let Some(id) = id {
+                progress.levels.entry(id.clone()).or_insert(level);
+            }
+        }
+    }
+}
+
+/// A profile's progress through one collection of levels
+#[derive(Serialize, Deserialize, Debug, Default)]
+pub struct CollectionProgress {
+    /// Progress on each level, keyed by level id
+    #[serde(default)]
+    pub levels: BTreeMap<String, LevelProgress>,
+    /// Progress carried over from saves that only knew levels by their position in the
+    /// file. It moves over to `levels` the next time the collection is opened.
+    #[serde(default, skip_serializing_if = "BTreeMap::is_empty")]
+    pub by_index: BTreeMap<String, LevelProgress>,
+}
+
+#[derive(Serialize, Deserialize, Debug, Default, Clone)]
+pub struct LevelProgress {
+    #[serde(default)]
+    pub solved: bool,
+    /// Passed over for now, it counts as done when unlocking the levels after it
+    #[serde(default, skip_serializing_if = "std::ops::Not::not")]
+    pub skipped: bool,
+    /// The best (fewest moves) LURD solution
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub solution: Option<String>,
+    /// The moves played so far if the level was left unfinished
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub in_progress: Option<String>,
+    /// What the solver made of the level the first time it was won
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub optimal: Option<Optimal>,
+}
+
+/// The fewest moves a level can be solved in, kept so the solver only runs once a level
+#[derive(Serialize, Deserialize, Debug, Clone, Copy, PartialEq)]
+#[serde(rename_all = "snake_case")]
+pub enum Optimal {
+    Moves(usize),
+    /// The level was too big for the solver to work out
+    Unknown,
+}
+
+/// Where a set of levels came from and how to tell them apart, so progress survives the
+/// levels being reordered or added to
+#[derive(Debug, Default, Clone)]
+pub struct Collection {
+    /// Taken from the file name, progress in saves is kept under it
+    pub name: String,
+    /// From the file's `Title:` line, or the name if it doesn't have one
+    pub title: String,
+    pub author: Option<String>,
+    /// A hash of the whole file
+    pub hash: String,
+    /// A hash of each level's starting layout, in the same order as the worlds
+    pub level_ids: Vec<String>,
 }
 
 #[derive(Debug)]
 pub struct Game {
     pub window: GameWindow,
     pub worlds: Vec<World>,
+    pub collection: Collection,
     pub world_index: usize,
     pub history: Vec<World>,
+    /// The moves that took the starting world to the current one, in LURD notation
+    pub moves: String,
+    /// The forward game, parked while the level is being played in reverse
+    pub reverse: Option<ReverseGame>,
+    /// The help overlay is up, the game waits underneath it
+    pub help: bool,
+    /// How long the current level has been played for
+    pub clock: Stopwatch,
+    /// How the level is laid down for playing, the identity unless it's played mirrored
+    pub symmetry: Symmetry,
+    /// What happened to the last thing asked for, like a copy or a paste
+    pub message: Option<String>,
+}
+
+/// Time that only counts while it's running, so it can be stopped for a pause
+#[derive(Debug, Default)]
+pub struct Stopwatch {
+    elapsed: Duration,
+    running_since: Option<Instant>,
+}
+
+impl Stopwatch {
+    /// Starts again from nothing
+    pub fn restart(&mut self) {
+        self.elapsed = Duration::ZERO;
+        self.running_since = Some(Instant::now());
+    }
+
+    pub fn stop(&mut self) {
+        if let Some(since) = self.running_since.take() {
+            self.elapsed += since.elapsed();
+        }
+    }
+
+    pub fn resume(&mut self) {
+        self.running_since.get_or_insert_with(Instant::now);
+    }
+
+    pub fn elapsed(&self) -> Duration {
+        self.elapsed
+            + self
+                .running_since
+                .map_or(Duration::ZERO, |since| since.elapsed())
+    }
+}
+
+#[derive(Debug)]
+pub struct ReverseGame {
+    pub world: World,
+    pub history: Vec<World>,
+    pub moves: String,
 }
 
 impl Game {
+    /// A game of `worlds` from `collection`, nothing played yet on the level at
+    /// `world_index`
+    pub fn new(worlds: Vec<World>, collection: Collection, world_index: usize) -> Game {
+        Game {
+            window: GameWindow::new(worlds[world_index].clone(), Zoom::Middle),
+            worlds,
+            collection,
+            world_index,
+            history: Vec::new(),
+            moves: String::new(),
+            reverse: None,
+            help: false,
+            clock: Stopwatch::default(),
+            symmetry: Symmetry::IDENTITY,
+            message: None,
+        }
+    }
+
+    /// Swaps in another collection of levels, starting on `level_index`
+    pub fn open_collection(self: &mut Game, file: &CollectionFile, level_index: usize) {
+        self.collection = file.collection.clone();
+        self.worlds = file.worlds.clone();
+        self.change_level(level_index.min(self.worlds.len() - 1));
+    }
+
     pub fn change_level(self: &mut Game, level_index: usize) {
         self.world_index = level_index;
-        self.window.world = self.worlds[self.world_index].clone();
+        self.reverse = None;
+        self.help = false;
+        self.message = None;
+        self.clock.restart();
+        self.window.world = self.start_world();
         self.reload_world();
     }
 
+    /// The level under play before any moves, laid down the way it's being played
+    pub fn start_world(&self) -> World {
+        self.worlds[self.world_index].transformed(self.symmetry)
+    }
+
+    /// Moves played on the level as laid down, as moves on the level in its file. That's
+    /// how they're saved, so they still work played the other way round.
+    pub fn level_moves(&self, moves: &str) -> String {
+        transform::lurd(moves, self.symmetry.inverse())
+    }
+
     pub fn increment_level(self: &mut Game) {
         if self.world_index != self.worlds.len() - 1 {
             self.change_level(self.world_index + 1);
@@ -56,9 +422,11 @@ impl Game {
     /// Erasing your history, erases your past
     pub fn erase_history(self: &mut Game) {
         self.history.clear();
+        self.moves.clear();
     }
     /// Let's start over from the beginning.
     pub fn refresh_window(self: &mut Game) {
+        self.window.tween = None;
         if let Some(prev_world_state) = self.history.first() {
             self.window.world = prev_world_state.clone();
         }
@@ -76,20 +444,118 @@ pub struct Model {
     pub running_state: RunningState,
     pub game: Game,
     pub save_file: SaveFile,
+    pub replay: Option<Replay>,
+    pub collection_select: CollectionSelect,
+    pub level_select: LevelSelect,
+    pub profile_select: ProfileSelect,
+    pub keymap: Keymap,
+    /// The results of the level just won, while they're on screen
+    pub victory: Option<Victory>,
+    /// Something to tell the player on the menu, like a save that couldn't be loaded
+    pub notice: Option<String>,
+}
+
+impl Model {
+    /// Starts on the menu with `game` and `save_file`, every other screen as it is
+    /// before it's first opened
+    pub fn new(game: Game, save_file: SaveFile) -> Model {
+        Model {
+            running_state: RunningState::Menu,
+            game,
+            save_file,
+            replay: None,
+            collection_select: CollectionSelect::default(),
+            level_select: LevelSelect::default(),
+            profile_select: ProfileSelect::default(),
+            keymap: Keymap::default(),
+            victory: None,
+            notice: None,
+        }
+    }
+
+    /// The active profile's progress on the level at `index`
+    pub fn level_progress(&self, index: usize) -> Option<&LevelProgress> {
+        self.save_file.current().level(&self.game.collection, index)
+    }
+
+    pub fn level_progress_mut(&mut self, index: usize) -> &mut LevelProgress {
+        self.save_file
+            .current_mut()
+            .level_mut(&self.game.collection, index)
+    }
 }
 
-#[derive(Debug, PartialEq, Eq)]
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
 // XXX: Ambiguous name?
 pub enum RunningState {
     Menu,
+    CollectionSelect,
     LevelSelect,
     Game,
+    Replay,
+    Profiles,
+    Victory,
+    Summary,
     Done,
 }
 
+/// A collection's progress added up, counting the best solution to each level
+#[derive(Debug, Default, PartialEq)]
+pub struct Totals {
+    pub levels: usize,
+    pub solved: usize,
+    pub skipped: usize,
+    pub moves: usize,
+    pub pushes: usize,
+}
+
+/// How a level was won, for the screen shown after it
+#[derive(Debug)]
+pub struct Victory {
+    /// The winning moves in LURD notation
+    pub moves: String,
+    pub time: Duration,
+    /// The shortest stored solution from before this win, if there was one
+    pub best: Option<usize>,
+    /// The fewest moves the level can be solved in, if the solver got there
+    pub optimal: Option<usize>,
+}
+
+pub enum SummaryAction {
+    Collections,
+    Levels,
+}
+
+pub enum VictoryAction {
+    Next,
+    Replay,
+    Retry,
+    Back,
+}
+
+/// Playback of a stored solution
+#[derive(Debug)]
+pub struct Replay {
+    pub window: GameWindow,
+    /// Every position in the solution, starting with the level as loaded
+    pub worlds: Vec<World>,
+    /// The solution being played, in LURD notation
+    pub moves: String,
+    /// Index into `worlds` of the position on screen
+    pub position: usize,
+    pub playing: bool,
+    /// Index into `replay::SPEEDS`
+    pub speed: usize,
+    pub last_step: Instant,
+    /// Where to go when the viewer is closed
+    pub return_to: RunningState,
+    pub message: Option<String>,
+}
+
 #[derive(PartialEq)]
 pub enum MenuAction {
     StartGame,
+    Profiles,
     Quit,
     EraseSaveData,
 }
@@ -99,12 +565,150 @@ pub enum LevelSelectAction {
     Up,
     Down,
     Select,
-    // PageUp,
-    // PageDown,
+    StartFresh,
+    /// Play the level flipped left to right
+    PlayMirrored,
+    /// Mark the level as skipped, or not skipped any more
+    ToggleSkip,
+    /// Switch the collection to the next unlock rule
+    CycleUnlock,
+    PageUp,
+    PageDown,
+    First,
+    Last,
+    Replay,
+    /// A digit typed towards a level number to jump to
+    JumpDigit(char),
+    StartSearch,
+    SearchChar(char),
+    /// Delete the last character of the search or level number being typed
+    Backspace,
+    /// Stop searching or jumping, going back to where we were
+    Cancel,
     Quit,
 }
 
+pub enum ProfileAction {
+    Up,
+    Down,
+    /// Play as the highlighted profile
+    Switch,
+    New,
+    Rename,
+    Delete,
+    ConfirmDelete,
+    TypeChar(char),
+    Backspace,
+    /// Finish typing a profile name
+    Confirm,
+    /// Stop typing a name or deleting
+    Cancel,
+    Back,
+}
+
+impl ProfileAction {
+    /// Actions that change the save file, so it needs writing out afterwards
+    pub fn changes_save_file(&self) -> bool {
+        matches!(
+            self,
+            ProfileAction::Switch
+                | ProfileAction::ConfirmDelete
+                | ProfileAction::Confirm
+        )
+    }
+}
+
+impl LevelSelectAction {
+    /// Whether the save file needs writing out after this action
+    pub fn changes_save_file(&self) -> bool {
+        matches!(
+            self,
+            LevelSelectAction::StartFresh
+                | LevelSelectAction::ToggleSkip
+                | LevelSelectAction::CycleUnlock
+        )
+    }
+}
+
+/// A level file found by the collection browser
+#[derive(Debug, Clone)]
+pub struct CollectionFile {
+    pub collection: Collection,
+    pub worlds: Vec<World>,
+}
+
+#[derive(Debug, Default)]
+pub struct CollectionSelect {
+    pub files: Vec<CollectionFile>,
+    pub list_state: ListState,
+    /// Files that were found but couldn't be loaded, and why
+    pub problems: Vec<String>,
+}
+
+#[derive(Debug, PartialEq)]
+pub enum CollectionSelectAction {
+    Up,
+    Down,
+    First,
+    Last,
+    Open,
+    Back,
+}
+
+impl CollectionSelectAction {
+    /// Whether the save file needs writing out after this action
+    pub fn changes_save_file(&self) -> bool {
+        matches!(self, CollectionSelectAction::Open)
+    }
+}
+
+/// Cursor and text entry state of the profile picker
+#[derive(Debug, Default)]
+pub struct ProfileSelect {
+    pub selected: usize,
+    pub editing: Option<ProfileEdit>,
+    pub confirm_delete: bool,
+}
+
+/// A profile name being typed
 #[derive(Debug)]
+pub struct ProfileEdit {
+    /// The profile being renamed, `None` for a new one
+    pub index: Option<usize>,
+    pub name: String,
+}
+
+/// Cursor and text entry state of the level select screen
+#[derive(Debug, Default)]
+pub struct LevelSelect {
+    pub list_state: ListState,
+    /// Level number typed so far
+    pub jump: String,
+    /// Title search typed so far, `None` when not searching
+    pub search: Option<String>,
+    /// The level selected when the search started, to go back to on cancel
+    pub search_origin: usize,
+    /// How many rows the list showed when it was last drawn
+    pub page_size: usize,
+}
+
+pub enum ReplayAction {
+    TogglePlay,
+    StepForward,
+    StepBack,
+    Faster,
+    Slower,
+    First,
+    Last,
+    Tick,
+    Paste(String),
+    ZoomClose,
+    ZoomMiddle,
+    ZoomFar,
+    Quit,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
 pub enum Direction {
     Left,
     Right,
@@ -112,6 +716,72 @@ pub enum Direction {
     Down,
 }
 
+impl Direction {
+    pub const ALL: [Direction; 4] = [
+        Direction::Left,
+        Direction::Right,
+        Direction::Up,
+        Direction::Down,
+    ];
+
+    pub fn opposite(&self) -> Direction {
+        match self {
+            Direction::Left => Direction::Right,
+            Direction::Right => Direction::Left,
+            Direction::Up => Direction::Down,
+            Direction::Down => Direction::Up,
+        }
+    }
+}
+
+/// What happened when the player tried to move in a `Direction`.
+///
+/// Box numbers count only the `SokoBox` entities of a `World`, in the order they appear in
+/// `World::entities`.
+#[derive(Debug, Clone, PartialEq)]
+pub enum MoveOutcome {
+    BlockedByWall,
+    BlockedByBox,
+    Walked {
+        from: Coordinate,
+        to: Coordinate,
+    },
+    Pushed {
+        box_index: usize,
+        from: Coordinate,
+        to: Coordinate,
+    },
+    /// A push that left every box on a goal
+    Solved {
+        box_index: usize,
+        from: Coordinate,
+        to: Coordinate,
+    },
+    /// Only in reverse mode, the player dragged a box along behind them
+    Pulled {
+        box_index: usize,
+        from: Coordinate,
+        to: Coordinate,
+    },
+}
+
+impl MoveOutcome {
+    pub fn is_blocked(&self) -> bool {
+        matches!(self, MoveOutcome::BlockedByWall | MoveOutcome::BlockedByBox)
+    }
+
+    pub fn is_push(&self) -> bool {
+        matches!(
+            self,
+            MoveOutcome::Pushed { .. } | MoveOutcome::Solved { .. }
+        )
+    }
+
+    pub fn is_pull(&self) -> bool {
+        matches!(self, MoveOutcome::Pulled { .. })
+    }
+}
+
 #[derive(Debug)]
 pub enum GameAction {
     None,
@@ -122,10 +792,17 @@ pub enum GameAction {
     ZoomClose,
     ZoomMiddle,
     ZoomFar,
+    ToggleAnimation,
+    ToggleReverse,
+    ToggleHelp,
+    CopyLevel,
+    CopyMoves,
+    /// Text pasted into the terminal, loaded as a level of its own
+    Paste(String),
     Win,
 }
 
-#[derive(Debug, Clone)]
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
 pub enum Zoom {
     Close,
     Middle,
@@ -182,13 +859,15 @@ impl Entity {
     }
 }
 
-type Board = Array2<Tile>;
+pub type Board = Array2<Tile>;
 
 #[derive(Debug, Clone)]
 pub struct World {
     pub name: String,
     pub board: Board,
     pub entities: Vec<Entity>,
+    /// `Key: value` lines that came with the level in its file, like its `Author`
+    pub meta: Vec<(String, String)>,
     #[allow(dead_code)]
     pub camera_position: Coordinate, // TODO: should I be on world or GameWindow for now?
 }
@@ -240,6 +919,113 @@ pub struct GameWindow {
     pub world: World,
     pub zoom: Zoom,
     pub debug: Vec<String>,
+    /// Slide entities between tiles instead of jumping straight there
+    pub animate: bool,
+    pub tween: Option<Tween>,
+}
+
+/// How long an entity takes to slide one tile
+pub const TWEEN_DURATION: Duration = Duration::from_millis(90);
+
+/// Entities sliding from where they were last drawn to where they are now
+#[derive(Debug, Clone)]
+pub struct Tween {
+    /// Where each entity started, in tiles, indexed like `World::entities`
+    pub from: Vec<(f32, f32)>,
+    pub started: Instant,
+    /// Counts every move made so the walk cycle keeps stepping along
+    pub step: usize,
+}
+
+/// Where to draw an entity relative to its tile, and which walking frame to use
+#[derive(Debug, Clone, Default)]
+pub struct Motion {
+    /// Offset in tiles
+    pub offset: (f32, f32),
+    pub walk_frame: Option<usize>,
+}
+
+impl GameWindow {
+    pub fn new(world: World, zoom: Zoom) -> GameWindow {
+        GameWindow {
+            world,
+            zoom,
+            debug: Vec::new(),
+            animate: true,
+            tween: None,
+        }
+    }
+
+    /// How far along the current tween is, from 0.0 to 1.0, eased in and out
+    fn tween_progress(&self) -> Option<f32> {
+        let tween = self.tween.as_ref()?;
+        let t = tween.started.elapsed().as_secs_f32() / TWEEN_DURATION.as_secs_f32();
+        if t >= 1.0 {
+            return None;
+        }
+        Some(t * t * (3.0 - 2.0 * t))
+    }
+
+    pub fn is_animating(&self) -> bool {
+        self.tween_progress().is_some()
+    }
+
+    /// How each entity should be drawn right now
+    pub fn motions(&self) -> Vec<Motion> {
+        let (Some(tween), Some(t)) = (&self.tween, self.tween_progress()) else {
+            return vec![Motion::default(); self.world.entities.len()];
+        };
+        self.world
+            .entities
+            .iter()
+            .zip(tween.from.iter())
+            .map(|(entity, (from_x, from_y))| {
+                let to = entity.get_position();
+                let offset = (
+                    (from_x - to.x as f32) * (1.0 - t),
+                    (from_y - to.y as f32) * (1.0 - t),
+                );
+                let moving = offset != (0.0, 0.0);
+                Motion {
+                    offset,
+                    walk_frame: (moving && matches!(entity, Entity::Player(_)))
+                        .then_some(tween.step),
+                }
+            })
+            .collect()
+    }
+
+    /// Where each entity is on screen right now, in tiles
+    pub fn drawn_positions(&self) -> Vec<(f32, f32)> {
+        self.world
+            .entities
+            .iter()
+            .zip(self.motions())
+            .map(|(entity, motion)| {
+                let position = entity.get_position();
+                (
+                    position.x as f32 + motion.offset.0,
+                    position.y as f32 + motion.offset.1,
+                )
+            })
+            .collect()
+    }
+
+    /// Start sliding the entities over from `from`. Call this after changing `world`.
+    ///
+    /// Single cell zoom is too coarse to show anything in between, so it never animates.
+    pub fn slide_from(&mut self, from: Vec<(f32, f32)>) {
+        if !self.animate || matches!(self.zoom, Zoom::Far) {
+            self.tween = None;
+            return;
+        }
+        let step = self.tween.as_ref().map_or(0, |tween| tween.step + 1);
+        self.tween = Some(Tween {
+            from,
+            started: Instant::now(),
+            step,
+        });
+    }
 }
 
 #[derive(Debug, Clone)]
@@ -270,7 +1056,7 @@ pub type GlyphCells = Array2<GlyphCell>;
 #[derive(Debug, Clone)]
 pub enum RenderItem {
     Board(Board),
-    Entity(Entity),
+    Entity(Entity, Motion),
 }
 
 #[derive(Debug, Clone)]
@@ -0,0 +1,187 @@
+/*!
+ * Description:
+ *
+ * The screen shown after a level is won: how many moves and pushes it took, how long,
+ * and how that compares with the player's best and with the fewest moves the level can
+ * be solved in. From here the player goes on to the next level, watches their solution
+ * back or has another go.
+ *
+ * Usage:
+ *
+ *   victory::open(model); // when the level is solved, records the win and shows it
+ */
+
+use std::io;
+use std::time::Duration;
+
+use crate::board_layout::{self, BoardLayout, PanelSize};
+use crate::keymap::{Action, Screen};
+use crate::types::{Model, Optimal, RunningState, Victory, VictoryAction};
+use crate::{level_select, lurd, replay, soko_game, solver};
+use ratatui::{
+    crossterm::event::{self, Event},
+    widgets::{Block, Paragraph},
+    Frame,
+};
+
+/// The least room the results need to be worth showing
+const PANEL: PanelSize = PanelSize {
+    width: 36,
+    height: 12,
+};
+
+/// Records the level under play as solved, keeping the solution if it's the shortest
+/// yet, and shows how it went
+pub fn open(model: &mut Model) {
+    let game = &mut model.game;
+    game.clock.stop();
+    let moves = game.moves.clone();
+    let time = game.clock.elapsed();
+    let solution = game.level_moves(&moves);
+    let world = game.worlds[game.world_index].clone();
+
+    let level = model.level_progress_mut(model.game.world_index);
+    // The solver can take a while on big levels, so it only gets the one go
+    let optimal = *level.optimal.get_or_insert_with(|| {
+        solver::solve(&world, solver::DEFAULT_LIMIT)
+            .map_or(Optimal::Unknown, |solution| Optimal::Moves(solution.len()))
+    });
+    let best = level.solution.as_ref().map(|solution| solution.len());
+    level.solved = true;
+    level.skipped = false;
+    level.in_progress = None;
+    if best.is_none_or(|best| moves.len() < best) {
+        level.solution = Some(solution);
+    }
+
+    model.victory = Some(Victory {
+        moves,
+        time,
+        best,
+        optimal: match optimal {
+            Optimal::Moves(moves) => Some(moves),
+            Optimal::Unknown => None,
+        },
+    });
+    model.running_state = RunningState::Victory;
+}
+
+pub fn view(model: &mut Model, frame: &mut Frame) {
+    let Some(victory) = &model.victory else {
+        return;
+    };
+
+    let moves = victory.moves.len();
+    let best = match victory.best {
+        None => "First solve!".to_string(),
+        Some(best) if moves < best => format!("{} (new best!)", best),
+        Some(best) => best.to_string(),
+    };
+    let optimal = match victory.optimal {
+        Some(optimal) if moves == optimal => format!("{} (perfect!)", optimal),
+        Some(optimal) => optimal.to_string(),
+        None => "Unknown, too big to work out".to_string(),
+    };
+    let text = format!(
+        "
+    Moves:            {}
+    Pushes:           {}
+    Time:             {}
+
+    Your Best:        {}
+    Fewest Possible:  {}
+
+{}",
+        moves,
+        lurd::push_count(&victory.moves),
+        format_time(victory.time),
+        best,
+        optimal,
+        model.keymap.help(Screen::Victory),
+    );
+    let results = Paragraph::new(text).block(Block::bordered().title("~SOLVED!~"));
+
+    // The results matter more than the board, so they get the screen when both won't fit
+    let window = &model.game.window;
+    let Some(BoardLayout {
+        board: board_area,
+        zoom,
+        panel: Some(panel_area),
+    }) = board_layout::split(frame.area(), &window.world, window.zoom, PANEL)
+    else {
+        frame.render_widget(results, frame.area());
+        return;
+    };
+
+    let outer_left_block = Block::bordered().title(window.world.name.clone());
+    let inner_left = outer_left_block.inner(board_area);
+    frame.render_widget(outer_left_block, board_area);
+    let mut board = window.clone();
+    board.zoom = zoom;
+    frame.render_widget(board, inner_left);
+    frame.render_widget(results, panel_area);
+}
+
+/// Minutes, seconds and tenths, like `1:07.3`
+pub fn format_time(time: Duration) -> String {
+    let tenths = time.as_millis() / 100;
+    format!("{}:{:02}.{}", tenths / 600, tenths / 10 % 60, tenths % 10)
+}
+
+pub fn update(model: &mut Model, msg: VictoryAction) -> Option<VictoryAction> {
+    match msg {
+        VictoryAction::Next => {
+            model.victory = None;
+            let next = model.game.world_index + 1;
+            if next == model.game.worlds.len() {
+                // That was the last one, see how the whole collection went
+                model.running_state = RunningState::Summary;
+            } else if level_select::is_unlocked(model, next) {
+                model.game.increment_level();
+                soko_game::start_level(model, false);
+            } else {
+                model.running_state = RunningState::LevelSelect;
+            }
+        }
+        VictoryAction::Replay => {
+            let victory = model.victory.as_ref()?;
+            let world = model.game.start_world();
+            if let Ok(replay) =
+                replay::load(&world, &victory.moves, RunningState::Victory)
+            {
+                model.replay = Some(replay);
+                model.running_state = RunningState::Replay;
+            }
+        }
+        VictoryAction::Retry => {
+            model.victory = None;
+            soko_game::start_level(model, true);
+        }
+        VictoryAction::Back => {
+            model.victory = None;
+            model.running_state = RunningState::LevelSelect;
+        }
+    };
+    None
+}
+
+pub fn handle_event(model: &Model) -> io::Result<Option<VictoryAction>> {
+    if event::poll(Duration::from_millis(250))? {
+        if let Event::Key(key) = event::read()? {
+            if key.kind == event::KeyEventKind::Press {
+                return Ok(handle_key(model, key));
+            }
+        }
+    }
+    Ok(None)
+}
+
+pub fn handle_key(model: &Model, key: event::KeyEvent) -> Option<VictoryAction> {
+    match model.keymap.action(Screen::Victory, &key)? {
+        Action::Next => Some(VictoryAction::Next),
+        Action::Replay => Some(VictoryAction::Replay),
+        Action::Retry => Some(VictoryAction::Retry),
+        Action::Back => Some(VictoryAction::Back),
+        _ => None,
+    }
+}
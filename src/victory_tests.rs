@@ -0,0 +1,22 @@
+#[cfg(test)]
+mod tests {
+    use crate::test_util::new_model;
+    use crate::types::Optimal;
+    use crate::victory;
+
+    #[test]
+    fn test_optimal_is_worked_out_once() {
+        let mut model = new_model();
+        model.game.moves = "rrRR".to_string();
+        victory::open(&mut model);
+        assert_eq!(model.victory.as_ref().unwrap().optimal, Some(3));
+        let level = model.level_progress(0).unwrap();
+        assert_eq!(level.optimal, Some(Optimal::Moves(3)));
+        assert!(level.solved);
+
+        // The stored answer is used from then on, the solver isn't asked again
+        model.level_progress_mut(0).optimal = Some(Optimal::Unknown);
+        victory::open(&mut model);
+        assert_eq!(model.victory.as_ref().unwrap().optimal, None);
+    }
+}